@@ -0,0 +1,95 @@
+//! Compares `Debouncer::update` against the previous implementation, which
+//! counted the repetitions up towards the threshold.
+//!
+//! Run with `cargo bench --bench countdown`.
+
+use criterion::{black_box, criterion_group, criterion_main, Criterion};
+use derico::debouncer::{Debouncer, Edge};
+use derico::pin::PinState;
+
+/// The count-up implementation `Debouncer` used before.
+struct CountingDebouncer {
+    current_state: PinState,
+    next_state: PinState,
+    repetition_count: u8,
+    threshold: u8,
+}
+
+impl CountingDebouncer {
+    fn new(threshold: u8, inital_state: PinState) -> Self {
+        CountingDebouncer {
+            current_state: inital_state,
+            next_state: inital_state,
+            repetition_count: threshold,
+            threshold,
+        }
+    }
+
+    fn update(&mut self, state: PinState) -> Option<Edge<PinState>> {
+        if self.current_state == state {
+            self.next_state = state;
+            self.repetition_count = self.threshold;
+
+            None
+        } else if self.next_state != state {
+            self.next_state = state;
+            self.repetition_count = 1;
+
+            None
+        } else if self.repetition_count.saturating_add(1) < self.threshold {
+            self.repetition_count = self.repetition_count.saturating_add(1);
+
+            None
+        } else {
+            let from_state = self.current_state;
+
+            self.current_state = state;
+            self.repetition_count = self.threshold;
+
+            Some(Edge::new(from_state, state))
+        }
+    }
+}
+
+/// A bouncy sequence with runs of one to seven equal samples.
+fn samples() -> Vec<PinState> {
+    (0..4096u32)
+        .flat_map(|i| {
+            let state = if i % 2 == 0 {
+                PinState::High
+            } else {
+                PinState::Low
+            };
+            std::iter::repeat(state).take((i * 7 % 13 % 7 + 1) as usize)
+        })
+        .collect()
+}
+
+fn bench_countdown(c: &mut Criterion) {
+    let samples = samples();
+    let mut group = c.benchmark_group("u8 counter");
+
+    group.bench_function("count up", |b| {
+        b.iter(|| {
+            let mut debouncer = CountingDebouncer::new(4, PinState::Low);
+            samples
+                .iter()
+                .filter(|&&state| debouncer.update(black_box(state)).is_some())
+                .count()
+        })
+    });
+    group.bench_function("count down", |b| {
+        b.iter(|| {
+            let mut debouncer: Debouncer<PinState, u8> = Debouncer::new(4, PinState::Low);
+            samples
+                .iter()
+                .filter(|&&state| debouncer.update(black_box(state)).is_some())
+                .count()
+        })
+    });
+
+    group.finish();
+}
+
+criterion_group!(benches, bench_countdown);
+criterion_main!(benches);
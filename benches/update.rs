@@ -0,0 +1,60 @@
+//! Baseline throughput of `update` for the pin debouncer and the generic
+//! debouncer with three states.
+//!
+//! Run with `cargo bench --bench update`.
+
+use criterion::{black_box, criterion_group, criterion_main, Criterion, Throughput};
+use derico::debouncer::Debouncer;
+use derico::pattern::BouncePattern;
+use derico::pin::{PinState, SmallPinDebouncer};
+
+const SAMPLES: usize = 65536;
+
+#[derive(Debug, PartialEq, Clone, Copy)]
+enum Mode {
+    Off,
+    Low,
+    High,
+}
+
+fn bench_update(c: &mut Criterion) {
+    let samples: Vec<_> = BouncePattern::new(1, 48, PinState::Low)
+        .take(SAMPLES)
+        .collect();
+    let modes: Vec<_> = samples
+        .iter()
+        .enumerate()
+        .map(|(i, &state)| match (state, i / 512 % 2) {
+            (PinState::Low, _) => Mode::Off,
+            (PinState::High, 0) => Mode::Low,
+            (PinState::High, _) => Mode::High,
+        })
+        .collect();
+
+    let mut group = c.benchmark_group("update");
+    group.throughput(Throughput::Elements(SAMPLES as u64));
+
+    group.bench_function("SmallPinDebouncer", |b| {
+        b.iter(|| {
+            let mut debouncer = SmallPinDebouncer::new(4, PinState::Low);
+            samples
+                .iter()
+                .filter(|&&state| debouncer.update(black_box(state)).is_some())
+                .count()
+        })
+    });
+    group.bench_function("three states", |b| {
+        b.iter(|| {
+            let mut debouncer: Debouncer<Mode, u8> = Debouncer::new(4, Mode::Off);
+            modes
+                .iter()
+                .filter(|&&mode| debouncer.update(black_box(mode)).is_some())
+                .count()
+        })
+    });
+
+    group.finish();
+}
+
+criterion_group!(benches, bench_update);
+criterion_main!(benches);
@@ -0,0 +1,90 @@
+#![deny(unsafe_code)]
+
+use super::debouncer::{Debouncer, Edge};
+
+/// Debounces an ADC reading bucketed into bands.
+///
+/// The bands are defined by `N` ascending boundaries: a reading below the
+/// first boundary is in band 0, a reading at or above boundary `i` but below
+/// boundary `i + 1` is in band `i + 1`. With boundaries `[100, 200]`, the
+/// bands are `0..100`, `100..200` and `200..`. Changes of the band are then
+/// debounced like any other state, reporting the band indices in the edges.
+/// At most 255 boundaries are supported.
+#[derive(Debug)]
+pub struct AdcDebouncer<const N: usize> {
+    inner: Debouncer<u8, u8>,
+    boundaries: [u16; N],
+}
+
+impl<const N: usize> AdcDebouncer<N> {
+    // Evaluated when referenced, failing the build if the bands overflow `u8`
+    const VALID_BOUNDARIES: () = assert!(N <= 255, "at most 255 boundaries are supported");
+
+    pub fn new(boundaries: [u16; N], threshold: u8, inital_band: u8) -> Self {
+        let () = Self::VALID_BOUNDARIES;
+
+        AdcDebouncer {
+            inner: Debouncer::new(threshold, inital_band),
+            boundaries,
+        }
+    }
+
+    /// Returns the band of `reading`, without debouncing.
+    pub fn band_of(&self, reading: u16) -> u8 {
+        self.boundaries
+            .iter()
+            .take_while(|&&boundary| reading >= boundary)
+            .count() as u8
+    }
+
+    #[must_use = "a detected edge should be handled"]
+    pub fn update(&mut self, reading: u16) -> Option<Edge<u8>> {
+        self.inner.update(self.band_of(reading))
+    }
+
+    pub fn is_band(&self, band: u8) -> bool {
+        self.inner.is_state(band)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_band_of() {
+        let debouncer = AdcDebouncer::new([100, 200, 300], 2, 0);
+        assert_eq!(debouncer.band_of(0), 0);
+        assert_eq!(debouncer.band_of(99), 0);
+        assert_eq!(debouncer.band_of(100), 1);
+        assert_eq!(debouncer.band_of(299), 2);
+        assert_eq!(debouncer.band_of(u16::MAX), 3);
+
+        let debouncer = AdcDebouncer::new([], 2, 0);
+        assert_eq!(debouncer.band_of(u16::MAX), 0);
+    }
+
+    #[test]
+    fn test_noisy_ramp() {
+        let mut debouncer = AdcDebouncer::new([1000, 2000], 3, 0);
+        assert!(debouncer.is_band(0));
+
+        // Noise around the first boundary
+        for &reading in [900, 990, 1010, 995, 1005, 1020].iter() {
+            assert_eq!(debouncer.update(reading), None);
+        }
+        assert_eq!(debouncer.update(1050), Some(Edge::new(0, 1)));
+        assert!(debouncer.is_band(1));
+
+        // A spike into band 2 is ignored
+        assert_eq!(debouncer.update(2100), None);
+        assert_eq!(debouncer.update(1500), None);
+        assert!(debouncer.is_band(1));
+
+        for &reading in [1990, 2010, 2020].iter() {
+            assert_eq!(debouncer.update(reading), None);
+        }
+        assert_eq!(debouncer.update(2050), Some(Edge::new(1, 2)));
+        assert!(debouncer.is_band(2));
+    }
+}
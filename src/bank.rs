@@ -0,0 +1,81 @@
+#![deny(unsafe_code)]
+
+use super::debouncer::Edge;
+use super::pin::{PinState, SmallPinDebouncer};
+
+/// A bank of `N` pins debounced in lockstep with a shared threshold.
+#[derive(Debug)]
+pub struct PinBank<const N: usize> {
+    inner: [SmallPinDebouncer; N],
+}
+
+impl<const N: usize> PinBank<N> {
+    pub fn new(threshold: u8, inital_state: PinState) -> Self {
+        PinBank {
+            inner: [(); N].map(|()| SmallPinDebouncer::new(threshold, inital_state)),
+        }
+    }
+
+    /// Feeds one sample per pin and returns the edge of each pin.
+    #[must_use = "a detected edge should be handled"]
+    pub fn update(&mut self, states: [PinState; N]) -> [Option<Edge<PinState>>; N] {
+        let mut edges = [None; N];
+        for ((edge, debouncer), &state) in edges.iter_mut().zip(&mut self.inner).zip(&states) {
+            *edge = debouncer.update(state);
+        }
+
+        edges
+    }
+
+    /// Panics if `pin >= N`.
+    pub fn is_high(&self, pin: usize) -> bool {
+        self.inner[pin].is_high()
+    }
+
+    /// Panics if `pin >= N`.
+    pub fn is_low(&self, pin: usize) -> bool {
+        self.inner[pin].is_low()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use PinState::{High, Low};
+
+    #[test]
+    fn test_update() {
+        let mut bank: PinBank<4> = PinBank::new(2, Low);
+
+        assert_eq!(bank.update([High, Low, High, Low]), [None; 4]);
+        assert_eq!(
+            bank.update([High, Low, Low, Low]),
+            [Some(Edge::new(Low, High)), None, None, None]
+        );
+        assert_eq!(bank.update([High, Low, High, High]), [None; 4]);
+        assert_eq!(
+            bank.update([High, Low, High, High]),
+            [
+                None,
+                None,
+                Some(Edge::new(Low, High)),
+                Some(Edge::new(Low, High))
+            ]
+        );
+
+        assert!(bank.is_high(0));
+        assert!(bank.is_low(1));
+        assert!(bank.is_high(2));
+        assert!(bank.is_high(3));
+        assert!(!bank.is_low(0));
+    }
+
+    cfg_no_optional_counters! {
+        #[test]
+        fn test_ram_consumption() {
+            assert_eq!(std::mem::size_of::<PinBank<4>>(), 20);
+            assert_eq!(std::mem::size_of::<PinBank<8>>(), 40);
+        }
+    }
+}
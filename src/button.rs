@@ -0,0 +1,238 @@
+#![deny(unsafe_code)]
+
+use super::pin::{PinState, SmallPinDebouncer};
+
+/// A debounced button press, see `PressDetector`.
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum PressEvent {
+    /// The button was released before being held for long.
+    Short,
+    /// The button is still held and was held for longer than the hold count.
+    Long,
+}
+
+/// Distinguishes short presses from long presses of an active high button.
+///
+/// After a debounced `Low -> High` edge, the following `High` samples are
+/// counted. Once more than `hold_samples` were counted, `PressEvent::Long` is
+/// emitted while the button is still held and the release is ignored.
+/// Otherwise `PressEvent::Short` is emitted on the debounced release.
+#[derive(Debug)]
+pub struct PressDetector {
+    debouncer: SmallPinDebouncer,
+    hold_samples: u16,
+    held: u16,
+    pressed: bool,
+}
+
+impl PressDetector {
+    /// Creates a detector for a released button.
+    ///
+    /// A `hold_samples` of `u16::MAX` disables long presses.
+    pub fn new(threshold: u8, hold_samples: u16) -> Self {
+        PressDetector {
+            debouncer: SmallPinDebouncer::new(threshold, PinState::Low),
+            hold_samples,
+            held: 0,
+            pressed: false,
+        }
+    }
+
+    #[must_use = "a detected press should be handled"]
+    pub fn update(&mut self, state: PinState) -> Option<PressEvent> {
+        match self.debouncer.update(state).map(|edge| edge.into_parts()) {
+            Some((PinState::Low, PinState::High)) => {
+                self.pressed = true;
+                self.held = 0;
+
+                None
+            }
+            Some(_) => {
+                self.pressed = false;
+
+                if self.held > self.hold_samples {
+                    None
+                } else {
+                    Some(PressEvent::Short)
+                }
+            }
+            None if self.pressed && state == PinState::High && self.held <= self.hold_samples => {
+                // Saturates at a hold count of `u16::MAX`
+                self.held = self.held.saturating_add(1);
+
+                if self.held > self.hold_samples {
+                    Some(PressEvent::Long)
+                } else {
+                    None
+                }
+            }
+            None => None,
+        }
+    }
+}
+
+/// A debounced click, see `MultiClickDetector`.
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum ClickEvent {
+    Single,
+    Double,
+}
+
+/// Detects double clicks of an active high button.
+///
+/// A debounced `Low -> High` edge opens a window of `window` samples. A
+/// second rising edge within the window emits `ClickEvent::Double` right
+/// away, otherwise `ClickEvent::Single` is emitted once the window elapsed.
+/// Single clicks are therefore reported with a delay of `window` samples.
+#[derive(Debug)]
+pub struct MultiClickDetector {
+    debouncer: SmallPinDebouncer,
+    window: u16,
+    since_click: Option<u16>,
+}
+
+impl MultiClickDetector {
+    /// Creates a detector for a released button.
+    pub fn new(threshold: u8, window: u16) -> Self {
+        MultiClickDetector {
+            debouncer: SmallPinDebouncer::new(threshold, PinState::Low),
+            window,
+            since_click: None,
+        }
+    }
+
+    #[must_use = "a detected click should be handled"]
+    pub fn update(&mut self, state: PinState) -> Option<ClickEvent> {
+        let rising = matches!(
+            self.debouncer.update(state).map(|edge| edge.into_parts()),
+            Some((PinState::Low, PinState::High))
+        );
+
+        match self.since_click {
+            None => {
+                if rising {
+                    self.since_click = Some(0);
+                }
+
+                None
+            }
+            Some(_) if rising => {
+                self.since_click = None;
+
+                Some(ClickEvent::Double)
+            }
+            Some(samples) if samples + 1 >= self.window => {
+                self.since_click = None;
+
+                Some(ClickEvent::Single)
+            }
+            Some(samples) => {
+                self.since_click = Some(samples + 1);
+
+                None
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use PinState::{High, Low};
+
+    fn feed_clicks(detector: &mut MultiClickDetector, samples: &[PinState]) -> Option<ClickEvent> {
+        samples
+            .iter()
+            .filter_map(|&state| detector.update(state))
+            .last()
+    }
+
+    fn feed(detector: &mut PressDetector, samples: &[PinState]) -> Option<PressEvent> {
+        samples
+            .iter()
+            .filter_map(|&state| detector.update(state))
+            .last()
+    }
+
+    #[test]
+    fn test_short_tap() {
+        let mut detector = PressDetector::new(2, 5);
+        assert_eq!(feed(&mut detector, &[Low, High, Low, High, High]), None);
+        assert_eq!(feed(&mut detector, &[High, High, High]), None);
+        assert_eq!(detector.update(Low), None);
+        assert_eq!(detector.update(Low), Some(PressEvent::Short));
+        assert_eq!(feed(&mut detector, &[Low, Low, Low]), None);
+    }
+
+    #[test]
+    fn test_long_hold() {
+        let mut detector = PressDetector::new(2, 3);
+        assert_eq!(feed(&mut detector, &[High, High]), None);
+        assert_eq!(detector.update(High), None);
+        assert_eq!(detector.update(High), None);
+        // A bounce does not count as held, but does not abort the press
+        assert_eq!(detector.update(Low), None);
+        assert_eq!(detector.update(High), None);
+        assert_eq!(detector.update(High), Some(PressEvent::Long));
+
+        // Reported only once
+        assert_eq!(feed(&mut detector, &[High; 10]), None);
+    }
+
+    /// The longest hold count neither overflows nor reports a long press.
+    #[test]
+    fn test_max_hold_samples() {
+        let mut detector = PressDetector::new(2, u16::MAX);
+        assert_eq!(feed(&mut detector, &[High, High]), None);
+        for _ in 0..=u32::from(u16::MAX) + 1 {
+            assert_eq!(detector.update(High), None);
+        }
+        assert_eq!(feed(&mut detector, &[Low, Low]), Some(PressEvent::Short));
+    }
+
+    #[test]
+    fn test_release() {
+        let mut detector = PressDetector::new(2, 3);
+        assert_eq!(feed(&mut detector, &[High; 6]), Some(PressEvent::Long));
+        assert_eq!(feed(&mut detector, &[Low, Low, Low]), None);
+
+        // The next press starts counting anew
+        assert_eq!(feed(&mut detector, &[High, High, High, High]), None);
+        assert_eq!(feed(&mut detector, &[Low, Low]), Some(PressEvent::Short));
+    }
+
+    #[test]
+    fn test_single_click() {
+        let mut detector = MultiClickDetector::new(2, 6);
+        assert_eq!(feed_clicks(&mut detector, &[High, High]), None);
+        assert_eq!(
+            feed_clicks(&mut detector, &[High, Low, Low, Low, Low]),
+            None
+        );
+        assert_eq!(detector.update(Low), Some(ClickEvent::Single));
+        assert_eq!(feed_clicks(&mut detector, &[Low; 10]), None);
+    }
+
+    #[test]
+    fn test_double_click() {
+        let mut detector = MultiClickDetector::new(2, 6);
+        assert_eq!(feed_clicks(&mut detector, &[High, High, Low, Low]), None);
+        assert_eq!(detector.update(High), None);
+        assert_eq!(detector.update(High), Some(ClickEvent::Double));
+        assert_eq!(
+            feed_clicks(&mut detector, &[High, Low, Low, Low, Low]),
+            None
+        );
+        assert_eq!(feed_clicks(&mut detector, &[Low; 10]), None);
+
+        // Too slow for a double click
+        let slow = [High, High, Low, Low, Low, Low, Low, Low, High, High];
+        assert_eq!(feed_clicks(&mut detector, &slow), Some(ClickEvent::Single));
+        assert_eq!(
+            feed_clicks(&mut detector, &[Low; 10]),
+            Some(ClickEvent::Single)
+        );
+    }
+}
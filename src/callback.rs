@@ -0,0 +1,58 @@
+#![deny(unsafe_code)]
+
+use super::debouncer::{Counter, DebounceState, Debouncer, Edge};
+
+/// A debouncer calling a handler on every debounced edge.
+///
+/// The handler is stored alongside the debouncer, so it is bigger than a
+/// plain `Debouncer` by the size of the closure.
+pub struct CallbackDebouncer<T, S, F> {
+    inner: Debouncer<T, S>,
+    on_edge: F,
+}
+
+impl<T, S, F> CallbackDebouncer<T, S, F>
+where
+    T: DebounceState,
+    S: Counter,
+    F: FnMut(Edge<T>),
+{
+    pub fn new(threshold: S, inital_state: T, on_edge: F) -> Self {
+        CallbackDebouncer {
+            inner: Debouncer::new(threshold, inital_state),
+            on_edge,
+        }
+    }
+
+    /// Feeds a sample and calls the handler if an edge was detected.
+    pub fn update(&mut self, state: T) {
+        if let Some(edge) = self.inner.update(state) {
+            (self.on_edge)(edge);
+        }
+    }
+
+    pub fn is_state(&self, state: T) -> bool {
+        self.inner.is_state(state)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::pin::PinState::{self, High, Low};
+    use std::vec::Vec;
+
+    #[test]
+    fn test_callback() {
+        let mut edges = Vec::new();
+        let mut debouncer: CallbackDebouncer<PinState, u8, _> =
+            CallbackDebouncer::new(2, Low, |edge| edges.push(edge));
+
+        for &state in [High, High, High, Low, High, Low, Low].iter() {
+            debouncer.update(state);
+        }
+        assert!(debouncer.is_state(Low));
+
+        assert_eq!(edges, [Edge::new(Low, High), Edge::new(High, Low)]);
+    }
+}
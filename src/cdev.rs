@@ -0,0 +1,184 @@
+#![deny(unsafe_code)]
+
+use super::debouncer::Edge;
+use super::pin::{PinState, SmallPinDebouncer};
+
+/// An edge event of a line, like `gpio_cdev::LineEvent`.
+pub trait LineEventLike {
+    /// The time of the event in nanoseconds.
+    fn timestamp(&self) -> u64;
+
+    /// The level of the line after the event.
+    fn level(&self) -> PinState;
+}
+
+impl LineEventLike for gpio_cdev::LineEvent {
+    fn timestamp(&self) -> u64 {
+        gpio_cdev::LineEvent::timestamp(self)
+    }
+
+    fn level(&self) -> PinState {
+        match self.event_type() {
+            gpio_cdev::EventType::RisingEdge => PinState::High,
+            gpio_cdev::EventType::FallingEdge => PinState::Low,
+        }
+    }
+}
+
+/// Debounces the edge events of a Linux GPIO line.
+///
+/// The events are turned into samples taken every `sample_period` ns: the
+/// level reported by an event is held until the next event, and every full
+/// period in between yields one sample of it. A pulse is therefore committed
+/// once it lasted `threshold` sample periods, and pulses shorter than a
+/// period may not be sampled at all. Since the line is only sampled when
+/// events are fed, `poll` has to be called to commit the level held after
+/// the last event.
+///
+/// If the kernel debounces the line as well, e.g. with a debounce period
+/// configured for the line, this filter is applied to the events it lets
+/// through. The effective debounce time is then at least the longer of the
+/// two periods. Lost events do no harm, as every event reports the level
+/// instead of a toggle.
+#[derive(Debug)]
+pub struct LineEventDebouncer {
+    debouncer: SmallPinDebouncer,
+    threshold: u8,
+    sample_period: u64,
+    level: PinState,
+    // Time up to which the line has been sampled, unset before the first
+    // event or poll
+    sampled_until: Option<u64>,
+}
+
+impl LineEventDebouncer {
+    /// Creates a debouncer sampling the line every `sample_period` ns, which
+    /// is assumed to be in `inital_state` until the first event.
+    pub fn new(threshold: u8, sample_period: u64, inital_state: PinState) -> Self {
+        LineEventDebouncer {
+            debouncer: SmallPinDebouncer::new(threshold, inital_state),
+            threshold,
+            sample_period: sample_period.max(1),
+            level: inital_state,
+            sampled_until: None,
+        }
+    }
+
+    /// Samples the line up to the event and then takes over its level.
+    #[must_use = "a detected edge should be handled"]
+    pub fn update(&mut self, event: &impl LineEventLike) -> Option<Edge<PinState>> {
+        let edge = self.poll(event.timestamp());
+        self.level = event.level();
+
+        edge
+    }
+
+    /// Samples the line up to `now`, in nanoseconds on the clock of the
+    /// event timestamps.
+    #[must_use = "a detected edge should be handled"]
+    pub fn poll(&mut self, now: u64) -> Option<Edge<PinState>> {
+        let since = match self.sampled_until {
+            Some(since) => since,
+            None => {
+                self.sampled_until = Some(now);
+                return None;
+            }
+        };
+
+        let samples = now.saturating_sub(since) / self.sample_period;
+        // More samples of a single level than the threshold do not change
+        // the outcome anymore
+        let fed = samples.min(u64::from(self.threshold.max(2)));
+        let mut edge = None;
+        for _ in 0..fed {
+            if let Some(fed_edge) = self.debouncer.update(self.level) {
+                edge = Some(fed_edge);
+            }
+        }
+
+        self.sampled_until = Some(if samples > fed {
+            now
+        } else {
+            since + samples * self.sample_period
+        });
+
+        edge
+    }
+
+    pub fn is_high(&self) -> bool {
+        self.debouncer.is_high()
+    }
+
+    pub fn is_low(&self) -> bool {
+        self.debouncer.is_low()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use PinState::{High, Low};
+
+    const MS: u64 = 1_000_000;
+
+    struct MockEvent(u64, PinState);
+
+    impl LineEventLike for MockEvent {
+        fn timestamp(&self) -> u64 {
+            self.0
+        }
+
+        fn level(&self) -> PinState {
+            self.1
+        }
+    }
+
+    #[test]
+    fn test_clean_press() {
+        let mut debouncer = LineEventDebouncer::new(3, MS, Low);
+        assert_eq!(debouncer.update(&MockEvent(0, High)), None);
+        assert_eq!(debouncer.poll(2 * MS), None);
+        assert!(!debouncer.is_high());
+        assert_eq!(debouncer.poll(3 * MS), Some(Edge::new(Low, High)));
+        assert!(debouncer.is_high());
+
+        // The release is committed by the next event
+        assert_eq!(debouncer.update(&MockEvent(10 * MS, Low)), None);
+        assert_eq!(
+            debouncer.update(&MockEvent(20 * MS, High)),
+            Some(Edge::new(High, Low))
+        );
+    }
+
+    #[test]
+    fn test_bouncy_press() {
+        let mut debouncer = LineEventDebouncer::new(3, MS, Low);
+        let bounces = [
+            MockEvent(0, High),
+            MockEvent(MS, Low),
+            MockEvent(2 * MS, High),
+            MockEvent(2 * MS + MS / 2, Low),
+            MockEvent(2 * MS + 7 * MS / 10, High),
+        ];
+        for event in &bounces {
+            assert_eq!(debouncer.update(event), None);
+        }
+        assert!(debouncer.is_low());
+
+        assert_eq!(debouncer.poll(4 * MS), None);
+        assert_eq!(debouncer.poll(5 * MS), Some(Edge::new(Low, High)));
+    }
+
+    /// Long gaps between events are not sampled one by one.
+    #[test]
+    fn test_long_hold() {
+        let mut debouncer = LineEventDebouncer::new(3, MS, Low);
+        assert_eq!(debouncer.update(&MockEvent(0, High)), None);
+        assert_eq!(
+            debouncer.update(&MockEvent(1_000_000 * MS, Low)),
+            Some(Edge::new(Low, High))
+        );
+        assert_eq!(debouncer.poll(1_000_002 * MS), None);
+        assert_eq!(debouncer.poll(1_000_003 * MS), Some(Edge::new(High, Low)));
+    }
+}
@@ -0,0 +1,107 @@
+#![deny(unsafe_code)]
+
+use super::debouncer::{countdown, Countdown, Edge};
+
+/// A debouncer for states which are `Clone`, but not `Copy`.
+///
+/// It behaves exactly like `Debouncer`, but clones the state when committing
+/// it, so prefer `Debouncer` for `Copy` states.
+#[derive(Debug, PartialEq, Eq, Hash, Clone)]
+pub struct CloneDebouncer<T, S> {
+    current_state: T,
+    next_state: T,
+    remaining: S,
+    threshold: S,
+}
+
+impl<T, S> CloneDebouncer<T, S>
+where
+    T: Clone + PartialEq,
+    S: num::traits::Zero + num::traits::One + core::ops::Sub<Output = S> + PartialOrd + Copy,
+{
+    pub fn new(threshold: S, inital_state: T) -> Self {
+        CloneDebouncer {
+            next_state: inital_state.clone(),
+            current_state: inital_state,
+            remaining: S::zero(),
+            threshold,
+        }
+    }
+
+    #[must_use = "a detected edge should be handled"]
+    pub fn update(&mut self, state: T) -> Option<Edge<T>> {
+        let is_current = self.current_state == state;
+        let is_next = self.next_state == state;
+
+        match countdown(&mut self.remaining, self.threshold, is_current, is_next) {
+            Countdown::Stable | Countdown::Started => {
+                self.next_state = state;
+
+                None
+            }
+            Countdown::Continued => None,
+            Countdown::Committed => {
+                let from_state = core::mem::replace(&mut self.current_state, state.clone());
+
+                Some(Edge::new(from_state, state))
+            }
+        }
+    }
+
+    pub fn is_state(&self, state: &T) -> bool {
+        self.current_state == self.next_state && self.current_state == *state
+    }
+
+    /// Returns the committed state.
+    pub fn current_state(&self) -> &T {
+        &self.current_state
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::debouncer::Debouncer;
+    use std::string::{String, ToString};
+    use std::vec::Vec;
+
+    #[test]
+    fn test_string_states() {
+        let mut debouncer: CloneDebouncer<String, u8> = CloneDebouncer::new(2, "idle".to_string());
+        assert!(debouncer.is_state(&"idle".to_string()));
+        assert_eq!(debouncer.update("busy".to_string()), None);
+        assert!(!debouncer.is_state(&"idle".to_string()));
+        assert_eq!(
+            debouncer.update("busy".to_string()),
+            Some(Edge::new("idle".to_string(), "busy".to_string()))
+        );
+        assert!(debouncer.is_state(&"busy".to_string()));
+        assert_eq!(debouncer.current_state(), "busy");
+    }
+
+    /// The edges match those of `Debouncer` for the same samples.
+    #[test]
+    fn test_same_as_copy() {
+        let samples = [
+            "a", "b", "b", "c", "c", "c", "a", "c", "b", "b", "b", "b", "a", "a",
+        ];
+
+        for threshold in 1..=4 {
+            let mut copy: Debouncer<&str, u8> = Debouncer::new(threshold, "a");
+            let mut clone: CloneDebouncer<String, u8> =
+                CloneDebouncer::new(threshold, "a".to_string());
+
+            let expected: Vec<_> = samples
+                .iter()
+                .map(|&sample| copy.update(sample).map(|edge| edge.map(String::from)))
+                .collect();
+            let actual: Vec<_> = samples
+                .iter()
+                .map(|&sample| clone.update(sample.to_string()))
+                .collect();
+
+            assert_eq!(actual, expected, "threshold {}", threshold);
+            assert!(expected.iter().any(Option::is_some));
+        }
+    }
+}
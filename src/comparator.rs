@@ -0,0 +1,117 @@
+#![deny(unsafe_code)]
+
+use super::debouncer::{countdown, Countdown, Edge};
+
+/// A debouncer comparing states with a custom function instead of `==`.
+///
+/// This allows debouncing states without a sensible `PartialEq`, like `f32`
+/// readings compared within a tolerance. Otherwise it behaves like
+/// `Debouncer`: the edges report the committed sample and the sample
+/// committing the new state.
+pub struct ComparatorDebouncer<T, S, F> {
+    current_state: T,
+    next_state: T,
+    remaining: S,
+    threshold: S,
+    eq: F,
+}
+
+impl<T, S, F> ComparatorDebouncer<T, S, F>
+where
+    T: Copy,
+    S: num::traits::Zero + num::traits::One + core::ops::Sub<Output = S> + PartialOrd + Copy,
+    F: Fn(&T, &T) -> bool,
+{
+    pub fn new(threshold: S, inital_state: T, eq: F) -> Self {
+        ComparatorDebouncer {
+            current_state: inital_state,
+            next_state: inital_state,
+            remaining: S::zero(),
+            threshold,
+            eq,
+        }
+    }
+
+    #[must_use = "a detected edge should be handled"]
+    pub fn update(&mut self, state: T) -> Option<Edge<T>> {
+        let is_current = (self.eq)(&self.current_state, &state);
+        let is_next = (self.eq)(&self.next_state, &state);
+
+        match countdown(&mut self.remaining, self.threshold, is_current, is_next) {
+            // A sample close to the committed state is not kept, as closeness
+            // need not be transitive and the next sample would be compared
+            // to a drifted candidate
+            Countdown::Stable => {
+                self.next_state = self.current_state;
+
+                None
+            }
+            Countdown::Started => {
+                self.next_state = state;
+
+                None
+            }
+            Countdown::Continued => None,
+            Countdown::Committed => {
+                let from_state = self.current_state;
+                self.current_state = state;
+
+                Some(Edge::new(from_state, state))
+            }
+        }
+    }
+
+    pub fn is_state(&self, state: T) -> bool {
+        (self.eq)(&self.current_state, &self.next_state) && (self.eq)(&self.current_state, &state)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn close(a: &f32, b: &f32) -> bool {
+        (a - b).abs() < 0.1
+    }
+
+    #[test]
+    fn test_tolerance() {
+        let mut debouncer = ComparatorDebouncer::new(3u8, 1.0f32, close);
+        assert!(debouncer.is_state(1.05));
+
+        // Jitter within the tolerance
+        assert_eq!(debouncer.update(1.02), None);
+        assert_eq!(debouncer.update(0.97), None);
+        assert!(debouncer.is_state(1.0));
+
+        assert_eq!(debouncer.update(2.0), None);
+        assert_eq!(debouncer.update(2.04), None);
+        assert!(!debouncer.is_state(2.0));
+        assert_eq!(debouncer.update(1.98), Some(Edge::new(1.0, 1.98)));
+        assert!(debouncer.is_state(2.0));
+
+        // A glitch restarts the count
+        assert_eq!(debouncer.update(3.0), None);
+        assert_eq!(debouncer.update(2.01), None);
+        assert!(debouncer.is_state(2.0));
+        assert_eq!(debouncer.update(3.0), None);
+        assert_eq!(debouncer.update(3.0), None);
+        assert_eq!(debouncer.update(3.0), Some(Edge::new(1.98, 3.0)));
+        assert!(debouncer.is_state(3.05));
+    }
+
+    /// Closeness is not transitive, so samples drifting away from the
+    /// committed state have to be confirmed like any other transition.
+    #[test]
+    fn test_drift() {
+        let mut debouncer = ComparatorDebouncer::new(3u8, 1.0f32, close);
+        assert_eq!(debouncer.update(1.08), None);
+        assert!(debouncer.is_state(1.0));
+
+        // Close to 1.08, but not to the committed 1.0
+        assert_eq!(debouncer.update(1.15), None);
+        assert!(!debouncer.is_state(1.0));
+        assert_eq!(debouncer.update(1.15), None);
+        assert_eq!(debouncer.update(1.15), Some(Edge::new(1.0, 1.15)));
+    }
+}
@@ -1,118 +1,2165 @@
 #![deny(unsafe_code)]
 
-#[derive(Debug, PartialEq, Clone, Copy)]
+#[derive(Debug, PartialEq, Eq, Hash, Clone, Copy)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Edge<T> {
     from: T,
     to: T,
 }
 
-impl<T> Edge<T> {
-    pub fn new(from: T, to: T) -> Self {
-        Edge { from, to }
+impl<T> Edge<T> {
+    /// Creates an edge without checking the states, see `try_new`.
+    pub fn new(from: T, to: T) -> Self {
+        Edge { from, to }
+    }
+
+    /// Returns the `(from, to)` states of the edge.
+    pub fn into_parts(self) -> (T, T) {
+        (self.from, self.to)
+    }
+
+    /// Converts both states of the edge with `f`.
+    pub fn map<U>(self, f: impl Fn(T) -> U) -> Edge<U> {
+        Edge::new(f(self.from), f(self.to))
+    }
+}
+
+impl<T> Edge<T>
+where
+    T: PartialEq,
+{
+    /// Creates an edge, or returns `None` if both states are equal.
+    ///
+    /// A debouncer never reports an edge between equal states, so this is
+    /// preferable to `new` when creating edges e.g. from user input.
+    pub fn try_new(from: T, to: T) -> Option<Self> {
+        if from == to {
+            None
+        } else {
+            Some(Edge::new(from, to))
+        }
+    }
+
+    /// Returns whether both edges connect the same two states, regardless of
+    /// the direction.
+    pub fn same_transition(&self, other: &Edge<T>) -> bool {
+        (self.from == other.from && self.to == other.to)
+            || (self.from == other.to && self.to == other.from)
+    }
+}
+
+/// The requirements on the states of a `Debouncer`.
+///
+/// This is implemented for all `PartialEq + Copy` types.
+pub trait DebounceState: PartialEq + Copy {}
+
+impl<T> DebounceState for T where T: PartialEq + Copy {}
+
+/// The requirements on the counter of a `Debouncer`.
+///
+/// This is implemented for all types with the required operations, which
+/// includes all primitive integers. Only the optional counters like
+/// `stable-count` saturate, but `SaturatingAdd` is required regardless, so
+/// that enabling a feature never tightens the bounds.
+pub trait Counter:
+    num::traits::Zero
+    + num::traits::One
+    + num::traits::SaturatingAdd
+    + core::ops::Sub<Output = Self>
+    + PartialEq
+    + PartialOrd
+    + Copy
+{
+}
+
+impl<S> Counter for S where
+    S: num::traits::Zero
+        + num::traits::One
+        + num::traits::SaturatingAdd
+        + core::ops::Sub<Output = S>
+        + PartialEq
+        + PartialOrd
+        + Copy
+{
+}
+
+/// Returns the samples needed to commit a state, as thresholds below two
+/// behave like two.
+pub(crate) fn effective_threshold<S>(threshold: S) -> S
+where
+    S: num::traits::One + core::ops::Add<Output = S> + PartialOrd + Copy,
+{
+    if threshold > S::one() {
+        threshold
+    } else {
+        S::one() + S::one()
+    }
+}
+
+/// How a sample advanced the countdown of a debouncer, see `countdown`.
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub(crate) enum Countdown {
+    /// The committed state, aborting any transition in progress.
+    Stable,
+    /// A new candidate, replacing the previous one if any.
+    Started,
+    /// Another sample of the candidate.
+    Continued,
+    /// The last sample needed, committing the candidate.
+    Committed,
+}
+
+/// Counts down the samples still needed to commit the candidate state, for
+/// a sample equal to the committed state and/or the candidate state.
+///
+/// This is the state machine of `Debouncer`, shared with the debouncers
+/// which cannot hold one, e.g. because their states are not `Copy`. The
+/// caller updates the states according to the returned step.
+pub(crate) fn countdown<S>(
+    remaining: &mut S,
+    threshold: S,
+    is_current: bool,
+    is_next: bool,
+) -> Countdown
+where
+    S: num::traits::Zero + num::traits::One + core::ops::Sub<Output = S> + PartialOrd + Copy,
+{
+    match (is_current, is_next) {
+        (true, _) => {
+            *remaining = S::zero();
+
+            Countdown::Stable
+        }
+        (false, false) => {
+            // The first sample counts, but a transition is never committed
+            // right away, even for thresholds below two
+            *remaining = effective_threshold(threshold) - S::one();
+
+            Countdown::Started
+        }
+        (false, true) if *remaining > S::one() => {
+            // A count above the threshold, e.g. left by a leaked
+            // `ThresholdMut`, is capped as if the candidate just started
+            let limit = effective_threshold(threshold);
+            if *remaining > limit {
+                *remaining = limit;
+            }
+            *remaining = *remaining - S::one();
+
+            Countdown::Continued
+        }
+        (false, true) => {
+            *remaining = S::zero();
+
+            Countdown::Committed
+        }
+    }
+}
+
+/// The direction of an edge between the two states of a `TwoState` type.
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum Direction {
+    /// Towards the high state.
+    Forward,
+    /// Towards the low state.
+    Backward,
+}
+
+/// A type with exactly two states, one of which is considered high.
+pub trait TwoState {
+    fn is_high(&self) -> bool;
+}
+
+impl<T> Edge<T>
+where
+    T: TwoState,
+{
+    pub fn direction(&self) -> Direction {
+        if self.to.is_high() {
+            Direction::Forward
+        } else {
+            Direction::Backward
+        }
+    }
+}
+
+/// A type whose values have a position, e.g. the discriminants of an enum.
+pub trait Ordinal {
+    fn ordinal(&self) -> i32;
+}
+
+impl<T> Edge<T>
+where
+    T: Ordinal,
+{
+    /// Returns the distance from the old to the new state, e.g. to sum up
+    /// the net movement of many edges.
+    pub fn delta(&self) -> i32 {
+        self.to.ordinal() - self.from.ordinal()
+    }
+}
+
+/// The outcome of a single `Debouncer::update_status`.
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum UpdateStatus<T, S> {
+    /// The committed state was sampled.
+    Stable,
+    /// A transition is in progress and needs `remaining` further samples.
+    Settling { remaining: S },
+    /// A transition was committed.
+    Changed(Edge<T>),
+}
+
+/// Which result `Debouncer::update_batch_mode` reports.
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum BatchMode {
+    /// The first edge, like `update_batch`.
+    FirstEdge,
+    /// The last edge, i.e. the one leading to the final committed state.
+    LastEdge,
+    /// The number of edges, like `update_batch_count`.
+    CountEdges,
+}
+
+/// The result of `Debouncer::update_batch_mode`.
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum BatchResult<T> {
+    /// The edge selected by `BatchMode::FirstEdge` or `BatchMode::LastEdge`,
+    /// if any.
+    Edge(Option<Edge<T>>),
+    /// The number of edges for `BatchMode::CountEdges`.
+    Count(usize),
+}
+
+/// Anything which can be sampled to drive a debouncer.
+pub trait DebounceSource {
+    type State;
+
+    fn sample(&mut self) -> Self::State;
+}
+
+/// The error of creating a debouncer with an invalid configuration.
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum DebouncerError {
+    /// The threshold is zero, or negative for a signed counter.
+    ZeroThreshold,
+    /// The samples still needed are not below the threshold, or do not
+    /// match whether a transition is in progress.
+    InvalidRemaining,
+}
+
+impl core::fmt::Display for DebouncerError {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            DebouncerError::ZeroThreshold => f.write_str("the threshold must be at least one"),
+            DebouncerError::InvalidRemaining => {
+                f.write_str("the samples remaining do not match the threshold and states")
+            }
+        }
+    }
+}
+
+/// A read-only copy of the internal state of a `Debouncer`, see
+/// `Debouncer::snapshot`.
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub struct DebouncerSnapshot<T, S> {
+    pub current_state: T,
+    pub next_state: T,
+    /// Samples of `next_state` counted towards `threshold`, zero while no
+    /// transition is in progress.
+    pub repetition_count: S,
+    pub threshold: S,
+}
+
+/// Debounces states of type `T`, counting samples with `S`.
+///
+/// Any unsigned primitive integer (`u8`, `u16`, `u32`, `u64`, `u128` and
+/// `usize`) can be used as counter, as long as it can hold the threshold.
+/// The counter is stored twice, so `u8` keeps the debouncer smallest.
+///
+/// Signed integers work as well, since the counter never goes below zero,
+/// but only offer half the range, e.g. thresholds up to 127 for `i8`. A
+/// negative threshold is treated like zero.
+///
+/// Two debouncers compare equal if they are in the same committed state and
+/// have the same transition, if any, in progress. Optional counters like the
+/// edge count have to match as well.
+///
+/// A debouncer is `Copy` whenever `T` and `S` are, so it can be stored in
+/// `Copy` aggregates and passed by value. A copy continues independently of
+/// the original.
+///
+/// Deserializing a debouncer validates it like `checked_new`, and rejects
+/// inconsistent counts as well.
+#[derive(Debug, PartialEq, Eq, Hash, Clone, Copy)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(
+    feature = "serde",
+    serde(
+        try_from = "DebouncerFields<T, S>",
+        bound(deserialize = "T: DebounceState + serde::Deserialize<'de>, \
+                             S: Counter + serde::Deserialize<'de>")
+    )
+)]
+pub struct Debouncer<T, S> {
+    current_state: T,
+    next_state: T,
+    // Counts down the samples of `next_state` still needed. It is zero while
+    // no transition is in progress and at least one otherwise.
+    remaining: S,
+    threshold: S,
+    #[cfg(feature = "edge-count")]
+    edge_count: u32,
+    #[cfg(feature = "stable-count")]
+    stable_for: S,
+    #[cfg(feature = "glitch-count")]
+    glitch_count: u32,
+    #[cfg(feature = "edge-latency")]
+    pending_for: S,
+    #[cfg(feature = "edge-latency")]
+    last_edge_latency: S,
+    #[cfg(feature = "last-edge")]
+    last_edge: Option<Edge<T>>,
+    #[cfg(feature = "settled-samples")]
+    settled_samples: S,
+}
+
+// The unvalidated fields of a deserialized `Debouncer`
+#[cfg(feature = "serde")]
+#[derive(serde::Deserialize)]
+struct DebouncerFields<T, S> {
+    current_state: T,
+    next_state: T,
+    remaining: S,
+    threshold: S,
+    #[cfg(feature = "edge-count")]
+    edge_count: u32,
+    #[cfg(feature = "stable-count")]
+    stable_for: S,
+    #[cfg(feature = "glitch-count")]
+    glitch_count: u32,
+    #[cfg(feature = "edge-latency")]
+    pending_for: S,
+    #[cfg(feature = "edge-latency")]
+    last_edge_latency: S,
+    #[cfg(feature = "last-edge")]
+    last_edge: Option<Edge<T>>,
+    #[cfg(feature = "settled-samples")]
+    settled_samples: S,
+}
+
+#[cfg(feature = "serde")]
+impl<T, S> core::convert::TryFrom<DebouncerFields<T, S>> for Debouncer<T, S>
+where
+    T: DebounceState,
+    S: Counter,
+{
+    type Error = DebouncerError;
+
+    fn try_from(fields: DebouncerFields<T, S>) -> Result<Self, DebouncerError> {
+        let debouncer = Debouncer::checked_new(fields.threshold, fields.current_state)?;
+        let valid_remaining = if fields.next_state == fields.current_state {
+            fields.remaining == S::zero()
+        } else {
+            // A pending state has been seen at least once
+            fields.remaining >= S::one() && fields.remaining < effective_threshold(fields.threshold)
+        };
+        if !valid_remaining {
+            return Err(DebouncerError::InvalidRemaining);
+        }
+
+        Ok(Debouncer {
+            next_state: fields.next_state,
+            remaining: fields.remaining,
+            #[cfg(feature = "edge-count")]
+            edge_count: fields.edge_count,
+            #[cfg(feature = "stable-count")]
+            stable_for: fields.stable_for,
+            #[cfg(feature = "glitch-count")]
+            glitch_count: fields.glitch_count,
+            #[cfg(feature = "edge-latency")]
+            pending_for: fields.pending_for,
+            #[cfg(feature = "edge-latency")]
+            last_edge_latency: fields.last_edge_latency,
+            #[cfg(feature = "last-edge")]
+            last_edge: fields.last_edge,
+            #[cfg(feature = "settled-samples")]
+            settled_samples: fields.settled_samples,
+            ..debouncer
+        })
+    }
+}
+
+impl<T, S> Debouncer<T, S>
+where
+    T: DebounceState,
+    S: Counter,
+{
+    /// Creates a debouncer committing a state after `threshold` samples.
+    ///
+    /// A threshold of zero is a configuration error and caught by a debug
+    /// assertion. In release builds, it behaves like a threshold of two,
+    /// see `checked_new` to reject it instead.
+    pub fn new(threshold: S, inital_state: T) -> Self {
+        debug_assert!(threshold >= S::one(), "threshold must not be zero");
+
+        Debouncer {
+            current_state: inital_state,
+            next_state: inital_state,
+            remaining: S::zero(),
+            threshold,
+            #[cfg(feature = "edge-count")]
+            edge_count: 0,
+            #[cfg(feature = "stable-count")]
+            stable_for: S::zero(),
+            #[cfg(feature = "glitch-count")]
+            glitch_count: 0,
+            #[cfg(feature = "edge-latency")]
+            pending_for: S::zero(),
+            #[cfg(feature = "edge-latency")]
+            last_edge_latency: S::zero(),
+            #[cfg(feature = "last-edge")]
+            last_edge: None,
+            #[cfg(feature = "settled-samples")]
+            settled_samples: S::zero(),
+        }
+    }
+
+    /// Like `new`, but returns an error for a threshold of zero in any build.
+    pub fn checked_new(threshold: S, inital_state: T) -> Result<Self, DebouncerError> {
+        if threshold < S::one() {
+            Err(DebouncerError::ZeroThreshold)
+        } else {
+            Ok(Debouncer::new(threshold, inital_state))
+        }
+    }
+
+    /// Creates a debouncer which already counted `count` samples of
+    /// `pending_state`, e.g. to resume a saved session.
+    ///
+    /// Returns `None` if the threshold is zero, like `checked_new`, or if
+    /// `count` exceeds `threshold`. If `pending_state` equals `inital_state`
+    /// or `count` is zero, no transition is in progress. As thresholds below
+    /// two behave like two, a count of one then leaves a single sample.
+    pub fn with_initial_count(
+        threshold: S,
+        inital_state: T,
+        pending_state: T,
+        count: S,
+    ) -> Option<Self> {
+        if count > threshold {
+            return None;
+        }
+
+        let mut debouncer = Debouncer::checked_new(threshold, inital_state).ok()?;
+        if pending_state != inital_state && count > S::zero() {
+            let threshold = effective_threshold(threshold);
+            debouncer.next_state = pending_state;
+            debouncer.remaining = if threshold - count > S::one() {
+                threshold - count
+            } else {
+                S::one()
+            };
+        }
+
+        Some(debouncer)
+    }
+
+    /// Creates a debouncer a single sample of `pending` away from committing
+    /// it, which is mostly useful in tests.
+    ///
+    /// `current` and `pending` are expected to differ. Otherwise the
+    /// debouncer is simply stable in `current`.
+    pub fn primed(threshold: S, current: T, pending: T) -> Self {
+        let mut debouncer = Debouncer::new(threshold, current);
+        if pending != current {
+            debouncer.next_state = pending;
+            debouncer.remaining = S::one();
+        }
+
+        debouncer
+    }
+
+    /// Feeds a sample into the debouncer.
+    ///
+    /// The debouncer counts consecutive samples of a single candidate state.
+    /// Any sample differing from the candidate replaces it ("last candidate
+    /// wins") and restarts the count, so with more than two states an edge is
+    /// only emitted once one state was sampled `threshold` times in a row.
+    ///
+    /// This already acts as a strict glitch filter: a single sample of the
+    /// committed state in between also discards the count, so `threshold`
+    /// strictly consecutive samples are required in any case.
+    ///
+    /// Every call takes a bounded number of steps independent of the input
+    /// and the threshold: the sample is compared to the committed state and
+    /// the candidate once each, there are no loops and nothing is allocated.
+    /// The result only depends on the debouncer and the sample.
+    ///
+    /// With the `log` feature, every debounced edge emits a `log::trace!`
+    /// record. The states are not formatted, so they need not be `Debug`.
+    #[must_use = "a detected edge should be handled"]
+    #[inline]
+    pub fn update(&mut self, state: T) -> Option<Edge<T>> {
+        match self.update_status(state) {
+            UpdateStatus::Changed(edge) => {
+                #[cfg(feature = "log")]
+                log::trace!("debounced an edge");
+                Some(edge)
+            }
+            UpdateStatus::Stable | UpdateStatus::Settling { .. } => None,
+        }
+    }
+
+    /// Like `update`, but distinguishes a stable state from a transition in
+    /// progress.
+    #[must_use = "a detected edge should be handled"]
+    #[inline]
+    pub fn update_status(&mut self, state: T) -> UpdateStatus<T, S> {
+        let is_current = self.current_state == state;
+        let is_next = self.next_state == state;
+
+        #[cfg(feature = "edge-latency")]
+        {
+            if self.pending_for > S::zero() || !is_current {
+                self.pending_for = self.pending_for.saturating_add(&S::one());
+            }
+        }
+        #[cfg(feature = "stable-count")]
+        {
+            if !is_current {
+                self.stable_for = S::zero();
+            }
+        }
+
+        #[cfg(feature = "glitch-count")]
+        let was_settling = self.next_state != self.current_state;
+
+        match countdown(&mut self.remaining, self.threshold, is_current, is_next) {
+            Countdown::Stable => {
+                #[cfg(feature = "glitch-count")]
+                {
+                    if !is_next {
+                        self.glitch_count = self.glitch_count.wrapping_add(1);
+                    }
+                }
+                self.next_state = state;
+                #[cfg(feature = "stable-count")]
+                {
+                    self.stable_for = self.stable_for.saturating_add(&S::one());
+                }
+                #[cfg(feature = "settled-samples")]
+                {
+                    self.settled_samples = self.settled_samples.saturating_add(&S::one());
+                }
+
+                UpdateStatus::Stable
+            }
+            Countdown::Started => {
+                #[cfg(feature = "glitch-count")]
+                {
+                    if was_settling {
+                        self.glitch_count = self.glitch_count.wrapping_add(1);
+                    }
+                }
+                self.next_state = state;
+
+                UpdateStatus::Settling {
+                    remaining: self.remaining,
+                }
+            }
+            Countdown::Continued => UpdateStatus::Settling {
+                remaining: self.remaining,
+            },
+            Countdown::Committed => {
+                let from_state = self.current_state;
+                let to_state = self.next_state;
+
+                self.current_state = state;
+                #[cfg(feature = "edge-count")]
+                {
+                    self.edge_count = self.edge_count.wrapping_add(1);
+                }
+                #[cfg(feature = "edge-latency")]
+                {
+                    self.last_edge_latency = self.pending_for;
+                    self.pending_for = S::zero();
+                }
+                #[cfg(feature = "last-edge")]
+                {
+                    self.last_edge = Some(Edge::new(from_state, to_state));
+                }
+                #[cfg(feature = "settled-samples")]
+                {
+                    self.settled_samples = S::zero();
+                }
+
+                UpdateStatus::Changed(Edge::new(from_state, to_state))
+            }
+        }
+    }
+
+    /// Feeds all samples into a new debouncer and returns it along with the
+    /// edges encountered, e.g. to analyze a recording offline.
+    #[cfg(feature = "alloc")]
+    pub fn replay(
+        threshold: S,
+        inital_state: T,
+        samples: impl IntoIterator<Item = T>,
+    ) -> (Self, alloc::vec::Vec<Edge<T>>) {
+        let mut debouncer = Debouncer::new(threshold, inital_state);
+        let edges = samples
+            .into_iter()
+            .filter_map(|sample| debouncer.update(sample))
+            .collect();
+
+        (debouncer, edges)
+    }
+
+    /// Like `update`, but only returns whether the committed state changed.
+    pub fn update_if_changed(&mut self, state: T) -> bool {
+        self.update(state).is_some()
+    }
+
+    /// Returns what `update` would return for `state`, without changing the
+    /// debouncer.
+    #[must_use]
+    pub fn peek_update(&self, state: T) -> Option<Edge<T>> {
+        // Goes around `update`, as nothing is actually debounced
+        let mut debouncer = *self;
+        match debouncer.update_status(state) {
+            UpdateStatus::Changed(edge) => Some(edge),
+            UpdateStatus::Stable | UpdateStatus::Settling { .. } => None,
+        }
+    }
+
+    /// Feeds a sample like `update` and passes the sample and the edge, if
+    /// any, to `sink`, e.g. for tracing.
+    #[must_use = "a detected edge should be handled"]
+    pub fn observe(
+        &mut self,
+        state: T,
+        sink: &mut impl FnMut(T, Option<Edge<T>>),
+    ) -> Option<Edge<T>> {
+        let edge = self.update(state);
+        sink(state, edge);
+
+        edge
+    }
+
+    /// Samples `source` once and feeds the sample into the debouncer.
+    #[must_use = "a detected edge should be handled"]
+    pub fn poll_source(&mut self, source: &mut impl DebounceSource<State = T>) -> Option<Edge<T>> {
+        self.update(source.sample())
+    }
+
+    /// Feeds all samples in order and returns the first edge encountered.
+    ///
+    /// The remaining samples are still fed after an edge, so the debouncer
+    /// always ends up in the state after the last sample.
+    #[must_use = "a detected edge should be handled"]
+    pub fn update_batch(&mut self, samples: &[T]) -> Option<Edge<T>> {
+        let mut first_edge = None;
+        for &sample in samples {
+            let edge = self.update(sample);
+            if first_edge.is_none() {
+                first_edge = edge;
+            }
+        }
+
+        first_edge
+    }
+
+    /// Feeds all samples in order and returns the number of edges encountered.
+    pub fn update_batch_count(&mut self, samples: &[T]) -> usize {
+        samples
+            .iter()
+            .filter(|&&sample| self.update(sample).is_some())
+            .count()
+    }
+
+    /// Feeds all samples in order, writes the edges encountered to `out` and
+    /// returns the number of edges written.
+    ///
+    /// Once `out` is full, further edges are dropped. The remaining samples
+    /// are still fed, so the debouncer always ends up in the state after the
+    /// last sample. A return value of `out.len()` may therefore mean that
+    /// edges were lost.
+    pub fn drain_edges(&mut self, samples: &[T], out: &mut [Edge<T>]) -> usize {
+        let mut written = 0;
+        for &sample in samples {
+            if let Some(edge) = self.update(sample) {
+                if let Some(slot) = out.get_mut(written) {
+                    *slot = edge;
+                    written += 1;
+                }
+            }
+        }
+
+        written
+    }
+
+    /// Feeds all samples in order and reports the edges according to `mode`.
+    ///
+    /// All samples are fed regardless of the mode, so the debouncer always
+    /// ends up in the state after the last sample.
+    #[must_use = "a detected edge should be handled"]
+    pub fn update_batch_mode(&mut self, samples: &[T], mode: BatchMode) -> BatchResult<T> {
+        match mode {
+            BatchMode::FirstEdge => BatchResult::Edge(self.update_batch(samples)),
+            BatchMode::LastEdge => BatchResult::Edge(
+                samples
+                    .iter()
+                    .fold(None, |last_edge, &sample| self.update(sample).or(last_edge)),
+            ),
+            BatchMode::CountEdges => BatchResult::Count(self.update_batch_count(samples)),
+        }
+    }
+
+    #[inline]
+    pub fn is_state(&self, state: T) -> bool {
+        self.current_state == self.next_state && self.current_state == state
+    }
+
+    /// Returns whether `state` is committed or a transition towards `state`
+    /// is in progress, e.g. whether a button is pressed or being pressed.
+    pub fn is_state_or_settling_to(&self, state: T) -> bool {
+        self.current_state == state || self.next_state == state
+    }
+
+    /// Returns whether no transition is in progress.
+    pub fn is_stable(&self) -> bool {
+        self.current_state == self.next_state
+    }
+
+    /// Returns whether a transition is in progress.
+    pub fn is_settling(&self) -> bool {
+        !self.is_stable()
+    }
+
+    /// Commits `state` immediately and discards any transition in progress,
+    /// without reporting an edge.
+    pub fn reset(&mut self, state: T) {
+        self.current_state = state;
+        self.next_state = state;
+        self.remaining = S::zero();
+        #[cfg(feature = "stable-count")]
+        {
+            self.stable_for = S::zero();
+        }
+        #[cfg(feature = "edge-latency")]
+        {
+            self.pending_for = S::zero();
+        }
+        #[cfg(feature = "settled-samples")]
+        {
+            self.settled_samples = S::zero();
+        }
+    }
+
+    /// Discards any transition in progress and keeps the committed state,
+    /// without reporting an edge.
+    ///
+    /// Unlike `reset`, optional counters like `stable_for` are kept.
+    pub fn reset_to_current(&mut self) {
+        self.next_state = self.current_state;
+        self.remaining = S::zero();
+    }
+
+    /// Commits `state` immediately like `reset`, but returns the edge if the
+    /// committed state changed.
+    #[must_use = "a detected edge should be handled"]
+    pub fn force_state(&mut self, state: T) -> Option<Edge<T>> {
+        let from_state = self.current_state;
+        self.reset(state);
+
+        if from_state != state {
+            Some(Edge::new(from_state, state))
+        } else {
+            None
+        }
+    }
+
+    /// Changes the threshold while keeping the committed state.
+    ///
+    /// A transition in progress keeps its count. If the threshold is lowered
+    /// to or below the samples counted so far, the next confirming sample
+    /// commits the transition.
+    ///
+    /// Like in `new`, a threshold of zero is caught by a debug assertion and
+    /// behaves like a threshold of two in release builds.
+    pub fn set_threshold(&mut self, threshold: S) {
+        debug_assert!(threshold >= S::one(), "threshold must not be zero");
+
+        if self.current_state != self.next_state {
+            // Only ever subtracts a smaller value, so the adjustment cannot
+            // overflow at either end of the counter range
+            let previous = effective_threshold(self.threshold);
+            let counted = if previous > self.remaining {
+                previous - self.remaining
+            } else {
+                S::zero()
+            };
+            let threshold = effective_threshold(threshold);
+            self.remaining = if threshold > counted {
+                threshold - counted
+            } else {
+                S::one()
+            };
+        }
+        self.threshold = threshold;
+    }
+
+    /// Borrows the threshold for in-place tuning.
+    ///
+    /// The debouncer only counts the samples still needed, so the transition
+    /// in progress is adjusted once the returned guard is dropped, exactly
+    /// like `set_threshold` would, including its debug assertion against a
+    /// zero threshold. Until then, the count may exceed the new
+    /// threshold, which is why the guard and not a plain `&mut S` is
+    /// returned. If the count reached the new threshold, the next confirming
+    /// sample commits the transition.
+    ///
+    /// If the guard is leaked, e.g. with `mem::forget`, the transition is not
+    /// adjusted. A lowered threshold then only takes effect with the next
+    /// confirming sample, which caps the samples still needed at the new
+    /// threshold, less the sample itself.
+    pub fn threshold_mut(&mut self) -> ThresholdMut<'_, T, S> {
+        ThresholdMut {
+            previous: self.threshold,
+            debouncer: self,
+        }
+    }
+
+    /// Changes the threshold and commits `state` like `reset`, e.g. when
+    /// switching between operating modes.
+    ///
+    /// No edge is reported, even if `state` differs from the committed state.
+    /// Like in `new`, a threshold of zero is caught by a debug assertion.
+    pub fn replace_threshold_and_reset(&mut self, threshold: S, state: T) {
+        debug_assert!(threshold >= S::one(), "threshold must not be zero");
+
+        self.threshold = threshold;
+        self.reset(state);
+    }
+
+    /// Returns how many edges were debounced, wrapping around on overflow.
+    ///
+    /// Edges reported by `force_state` are not counted.
+    #[cfg(feature = "edge-count")]
+    pub fn edge_count(&self) -> u32 {
+        self.edge_count
+    }
+
+    #[cfg(feature = "edge-count")]
+    pub fn reset_edge_count(&mut self) {
+        self.edge_count = 0;
+    }
+
+    /// Returns how many transitions were interrupted before reaching the
+    /// threshold, wrapping around on overflow.
+    ///
+    /// A transition is interrupted by a sample of the committed state or of
+    /// another candidate. Transitions discarded by `reset` or `force_state`
+    /// are not counted.
+    #[cfg(feature = "glitch-count")]
+    pub fn glitch_count(&self) -> u32 {
+        self.glitch_count
+    }
+
+    #[cfg(feature = "glitch-count")]
+    pub fn reset_glitch_count(&mut self) {
+        self.glitch_count = 0;
+    }
+
+    /// Returns how many samples it took to debounce the last edge, saturating
+    /// at the maximum of `S`, or zero if no edge was debounced yet.
+    ///
+    /// The samples are counted from the first sample differing from the
+    /// committed state after the previous edge or `reset`, up to and
+    /// including the committing sample. A clean transition therefore takes
+    /// `threshold` samples, while a noisy one takes longer.
+    #[cfg(feature = "edge-latency")]
+    pub fn last_edge_latency(&self) -> S {
+        self.last_edge_latency
+    }
+
+    /// Returns the last debounced edge, if any.
+    ///
+    /// Like for the edge count, edges reported by `force_state` are not
+    /// recorded.
+    #[cfg(feature = "last-edge")]
+    pub fn last_edge(&self) -> Option<Edge<T>> {
+        self.last_edge
+    }
+
+    /// Returns how many consecutive samples matched the committed state,
+    /// saturating at the maximum of `S`.
+    ///
+    /// The count starts from zero whenever another state is sampled or a
+    /// state is committed.
+    #[cfg(feature = "stable-count")]
+    pub fn stable_for(&self) -> S {
+        self.stable_for
+    }
+
+    /// Returns how many samples matched the committed state since the last
+    /// edge or `reset`, saturating at the maximum of `S`.
+    ///
+    /// Unlike `stable_for`, the count is kept across interruptions by other
+    /// states which did not lead to an edge.
+    #[cfg(feature = "settled-samples")]
+    pub fn settled_samples(&self) -> S {
+        self.settled_samples
+    }
+
+    /// Returns whether the committed state was sampled at least `limit` times
+    /// in a row, e.g. because a switch is stuck.
+    #[cfg(feature = "stable-count")]
+    pub fn is_stuck(&self, limit: S) -> bool {
+        self.stable_for >= limit
+    }
+
+    /// Returns how many more confirming samples are needed to commit the
+    /// pending state, or zero if no transition is in progress.
+    pub fn samples_remaining(&self) -> S {
+        self.remaining
+    }
+
+    /// Returns the pending state and the fraction of its confirming samples
+    /// counted so far, from 0.0 to below 1.0, e.g. to give feedback while a
+    /// button is held.
+    ///
+    /// While no transition is in progress, the committed state is returned
+    /// with a confidence of 1.0.
+    pub fn confidence(&self) -> (T, f32)
+    where
+        S: num::traits::ToPrimitive,
+    {
+        if self.is_stable() {
+            return (self.current_state, 1.0);
+        }
+
+        let snapshot = self.snapshot();
+        let count = snapshot.repetition_count.to_f32().unwrap_or(0.0);
+        let threshold = effective_threshold(snapshot.threshold)
+            .to_f32()
+            .unwrap_or(2.0);
+
+        (self.next_state, count / threshold)
+    }
+
+    /// Copies out the internal state, e.g. for structured logging.
+    pub fn snapshot(&self) -> DebouncerSnapshot<T, S> {
+        // Thresholds below two behave like two, so a pending state has
+        // been seen once even if `remaining` equals the threshold
+        let threshold = effective_threshold(self.threshold);
+        let repetition_count = if self.current_state == self.next_state {
+            S::zero()
+        } else if threshold > self.remaining {
+            threshold - self.remaining
+        } else {
+            S::zero()
+        };
+
+        DebouncerSnapshot {
+            current_state: self.current_state,
+            next_state: self.next_state,
+            repetition_count,
+            threshold: self.threshold,
+        }
+    }
+
+    /// Converts the counter type, e.g. to shrink a debouncer prototyped with
+    /// `u32` counters to `u8`.
+    ///
+    /// `f` is applied to the threshold and all counters, including those of
+    /// optional features. It has to map zero to zero and any other count to a
+    /// non-zero one, and should saturate rather than truncate counts not
+    /// fitting into `S2`, e.g. `|count| count.min(255) as u8`. A truncating
+    /// conversion like `as u8` silently changes the threshold.
+    pub fn map_counter<S2>(self, f: impl Fn(S) -> S2) -> Debouncer<T, S2>
+    where
+        S2: Counter,
+    {
+        Debouncer {
+            current_state: self.current_state,
+            next_state: self.next_state,
+            remaining: f(self.remaining),
+            threshold: f(self.threshold),
+            #[cfg(feature = "edge-count")]
+            edge_count: self.edge_count,
+            #[cfg(feature = "stable-count")]
+            stable_for: f(self.stable_for),
+            #[cfg(feature = "glitch-count")]
+            glitch_count: self.glitch_count,
+            #[cfg(feature = "edge-latency")]
+            pending_for: f(self.pending_for),
+            #[cfg(feature = "edge-latency")]
+            last_edge_latency: f(self.last_edge_latency),
+            #[cfg(feature = "last-edge")]
+            last_edge: self.last_edge,
+            #[cfg(feature = "settled-samples")]
+            settled_samples: f(self.settled_samples),
+        }
+    }
+}
+
+impl<T> Debouncer<T, u8>
+where
+    T: Copy,
+{
+    /// Like `new`, but usable in `const` and `static` items.
+    pub const fn new_const(threshold: u8, inital_state: T) -> Self {
+        debug_assert!(threshold >= 1, "threshold must not be zero");
+
+        Debouncer {
+            current_state: inital_state,
+            next_state: inital_state,
+            remaining: 0,
+            threshold,
+            #[cfg(feature = "edge-count")]
+            edge_count: 0,
+            #[cfg(feature = "stable-count")]
+            stable_for: 0,
+            #[cfg(feature = "glitch-count")]
+            glitch_count: 0,
+            #[cfg(feature = "edge-latency")]
+            pending_for: 0,
+            #[cfg(feature = "edge-latency")]
+            last_edge_latency: 0,
+            #[cfg(feature = "last-edge")]
+            last_edge: None,
+            #[cfg(feature = "settled-samples")]
+            settled_samples: 0,
+        }
+    }
+}
+
+/// Mutable access to the threshold of a `Debouncer`, see
+/// `Debouncer::threshold_mut`.
+pub struct ThresholdMut<'a, T, S>
+where
+    T: DebounceState,
+    S: Counter,
+{
+    debouncer: &'a mut Debouncer<T, S>,
+    previous: S,
+}
+
+impl<T, S> core::ops::Deref for ThresholdMut<'_, T, S>
+where
+    T: DebounceState,
+    S: Counter,
+{
+    type Target = S;
+
+    fn deref(&self) -> &S {
+        &self.debouncer.threshold
+    }
+}
+
+impl<T, S> core::ops::DerefMut for ThresholdMut<'_, T, S>
+where
+    T: DebounceState,
+    S: Counter,
+{
+    fn deref_mut(&mut self) -> &mut S {
+        &mut self.debouncer.threshold
+    }
+}
+
+impl<T, S> Drop for ThresholdMut<'_, T, S>
+where
+    T: DebounceState,
+    S: Counter,
+{
+    fn drop(&mut self) {
+        let threshold = self.debouncer.threshold;
+        self.debouncer.threshold = self.previous;
+        self.debouncer.set_threshold(threshold);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[allow(dead_code)]
+    #[derive(Debug, PartialEq, Eq, Hash, Clone, Copy)]
+    #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+    enum ABState {
+        A,
+        B,
+    }
+
+    #[derive(Debug, PartialEq, Clone, Copy)]
+    enum ABCState {
+        A,
+        B,
+        C,
+    }
+
+    #[derive(Debug)]
+    struct ABDebouncer {
+        inner: Debouncer<ABState, u8>,
+    }
+    
+    impl ABDebouncer {
+        fn new(threshold: u8, inital_state: ABState) -> Self {
+            ABDebouncer {
+                inner: Debouncer::new(threshold, inital_state),
+            }
+        }
+    
+        fn update(&mut self, state: ABState) -> Option<Edge<ABState>> {
+            self.inner.update(state)
+        }
+    
+        fn is_a(&self) -> bool {
+            self.inner.is_state(ABState::A)
+        }
+    
+        fn is_b(&self) -> bool {
+            self.inner.is_state(ABState::B)
+        }
+    }
+
+    #[test]
+    fn test_edge_try_new() {
+        assert_eq!(
+            Edge::try_new(ABState::A, ABState::B),
+            Some(Edge::new(ABState::A, ABState::B))
+        );
+        assert_eq!(Edge::try_new(ABState::B, ABState::B), None);
+    }
+
+    #[test]
+    fn test_edge_into_parts() {
+        let (from, to) = Edge::new(ABState::A, ABState::B).into_parts();
+        assert_eq!(from, ABState::A);
+        assert_eq!(to, ABState::B);
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn test_serde_round_trip() {
+        let edge = Edge::new(ABState::A, ABState::B);
+        let json = serde_json::to_string(&edge).unwrap();
+        assert_eq!(serde_json::from_str::<Edge<ABState>>(&json).unwrap(), edge);
+
+        // Two of three samples towards B are restored
+        let mut debouncer: Debouncer<ABState, u8> = Debouncer::new(3, ABState::A);
+        assert_eq!(debouncer.update(ABState::B), None);
+        assert_eq!(debouncer.update(ABState::B), None);
+
+        let json = serde_json::to_string(&debouncer).unwrap();
+        let mut restored: Debouncer<ABState, u8> = serde_json::from_str(&json).unwrap();
+        assert_eq!(serde_json::to_string(&restored).unwrap(), json);
+        assert_eq!(
+            restored.update(ABState::B),
+            Some(Edge::new(ABState::A, ABState::B))
+        );
+
+        // Invalid configurations and counts are rejected
+        let invalid = [
+            json.replace("\"threshold\":3", "\"threshold\":0"),
+            json.replace("\"remaining\":1", "\"remaining\":4"),
+            json.replace("\"remaining\":1", "\"remaining\":3"),
+            json.replace("\"remaining\":1", "\"remaining\":0"),
+            json.replace("\"next_state\":\"B\"", "\"next_state\":\"A\""),
+        ];
+        for json in invalid.iter() {
+            assert!(serde_json::from_str::<Debouncer<ABState, u8>>(json).is_err());
+        }
+    }
+
+    /// Interleaved candidates never commit, only a run of `threshold` does.
+    #[test]
+    fn test_three_states_interleaved() {
+        let mut debouncer: Debouncer<ABCState, u8> = Debouncer::new(3, ABCState::A);
+
+        for _ in 0..8 {
+            assert_eq!(debouncer.update(ABCState::B), None);
+            assert_eq!(debouncer.update(ABCState::C), None);
+            assert!(!debouncer.is_state(ABCState::A));
+        }
+        assert_eq!(debouncer.update(ABCState::C), None);
+        assert_eq!(
+            debouncer.update(ABCState::C),
+            Some(Edge::new(ABCState::A, ABCState::C))
+        );
+        assert!(debouncer.is_state(ABCState::C));
+    }
+
+    /// The last differing sample becomes the new candidate.
+    #[test]
+    fn test_three_states_last_candidate_wins() {
+        let mut debouncer: Debouncer<ABCState, u8> = Debouncer::new(3, ABCState::A);
+
+        // Two samples towards B are discarded by a single C
+        assert_eq!(debouncer.update(ABCState::B), None);
+        assert_eq!(debouncer.update(ABCState::B), None);
+        assert_eq!(debouncer.update(ABCState::C), None);
+        assert_eq!(debouncer.update(ABCState::B), None);
+        assert_eq!(debouncer.update(ABCState::B), None);
+        assert_eq!(
+            debouncer.update(ABCState::B),
+            Some(Edge::new(ABCState::A, ABCState::B))
+        );
+
+        // Returning to the committed state discards the candidate as well
+        assert_eq!(debouncer.update(ABCState::C), None);
+        assert_eq!(debouncer.update(ABCState::C), None);
+        assert_eq!(debouncer.update(ABCState::B), None);
+        assert!(debouncer.is_state(ABCState::B));
+        assert_eq!(debouncer.update(ABCState::A), None);
+        assert_eq!(debouncer.update(ABCState::A), None);
+        assert_eq!(
+            debouncer.update(ABCState::A),
+            Some(Edge::new(ABCState::B, ABCState::A))
+        );
+    }
+
+    #[test]
+    fn test_update_batch_single_edge() {
+        let mut debouncer: Debouncer<ABState, u8> = Debouncer::new(3, ABState::A);
+        let samples = [
+            ABState::A,
+            ABState::B,
+            ABState::A,
+            ABState::B,
+            ABState::B,
+            ABState::B,
+            ABState::B,
+        ];
+
+        assert_eq!(
+            debouncer.update_batch(&samples),
+            Some(Edge::new(ABState::A, ABState::B))
+        );
+        assert!(debouncer.is_state(ABState::B));
+        assert_eq!(debouncer.update_batch(&samples[..2]), None);
+        assert_eq!(debouncer.update_batch(&[]), None);
+    }
+
+    #[test]
+    fn test_update_batch_multiple_edges() {
+        let samples = [
+            ABState::B,
+            ABState::B,
+            ABState::A,
+            ABState::A,
+            ABState::B,
+            ABState::A,
+            ABState::B,
+            ABState::B,
+        ];
+
+        // The first of the three edges is returned, but all samples are consumed
+        let mut debouncer: Debouncer<ABState, u8> = Debouncer::new(2, ABState::A);
+        assert_eq!(
+            debouncer.update_batch(&samples),
+            Some(Edge::new(ABState::A, ABState::B))
+        );
+        assert!(debouncer.is_state(ABState::B));
+
+        let mut debouncer: Debouncer<ABState, u8> = Debouncer::new(2, ABState::A);
+        assert_eq!(debouncer.update_batch_count(&samples), 3);
+        assert!(debouncer.is_state(ABState::B));
+        assert_eq!(debouncer.update_batch_count(&samples[..1]), 0);
+    }
+
+    #[test]
+    fn test_drain_edges() {
+        use ABState::{A, B};
+        let samples = [B, B, A, A, B, A, B, B];
+        let fill = Edge::new(A, A);
+
+        // Exact fit
+        let mut debouncer: Debouncer<ABState, u8> = Debouncer::new(2, A);
+        let mut out = [fill; 3];
+        assert_eq!(debouncer.drain_edges(&samples, &mut out), 3);
+        assert_eq!(out, [Edge::new(A, B), Edge::new(B, A), Edge::new(A, B)]);
+
+        // Overflow drops the last edge, but still feeds all samples
+        let mut debouncer: Debouncer<ABState, u8> = Debouncer::new(2, A);
+        let mut out = [fill; 2];
+        assert_eq!(debouncer.drain_edges(&samples, &mut out), 2);
+        assert_eq!(out, [Edge::new(A, B), Edge::new(B, A)]);
+        assert!(debouncer.is_state(B));
+
+        let mut debouncer: Debouncer<ABState, u8> = Debouncer::new(2, A);
+        assert_eq!(debouncer.drain_edges(&samples, &mut []), 0);
+        assert!(debouncer.is_state(B));
+
+        // No edges leave `out` untouched
+        let mut out = [fill; 2];
+        assert_eq!(debouncer.drain_edges(&[B, A, B], &mut out), 0);
+        assert_eq!(out, [fill; 2]);
+    }
+
+    #[test]
+    fn test_update_batch_mode() {
+        let samples = [ABState::B, ABState::B, ABState::A, ABState::A, ABState::B];
+
+        let mut debouncer: Debouncer<ABState, u8> = Debouncer::new(2, ABState::A);
+        assert_eq!(
+            debouncer.update_batch_mode(&samples, BatchMode::FirstEdge),
+            BatchResult::Edge(Some(Edge::new(ABState::A, ABState::B)))
+        );
+        assert!(debouncer.is_settling());
+
+        let mut debouncer: Debouncer<ABState, u8> = Debouncer::new(2, ABState::A);
+        assert_eq!(
+            debouncer.update_batch_mode(&samples, BatchMode::LastEdge),
+            BatchResult::Edge(Some(Edge::new(ABState::B, ABState::A)))
+        );
+        assert!(debouncer.is_settling());
+
+        let mut debouncer: Debouncer<ABState, u8> = Debouncer::new(2, ABState::A);
+        assert_eq!(
+            debouncer.update_batch_mode(&samples, BatchMode::CountEdges),
+            BatchResult::Count(2)
+        );
+        assert!(debouncer.is_settling());
+
+        assert_eq!(
+            debouncer.update_batch_mode(&[], BatchMode::LastEdge),
+            BatchResult::Edge(None)
+        );
+    }
+
+    #[test]
+    fn test_samples_remaining() {
+        let mut debouncer: Debouncer<ABState, u8> = Debouncer::new(4, ABState::A);
+        assert_eq!(debouncer.samples_remaining(), 0);
+
+        assert_eq!(debouncer.update(ABState::A), None);
+        assert_eq!(debouncer.samples_remaining(), 0);
+
+        // Counts down with every confirming sample
+        assert_eq!(debouncer.update(ABState::B), None);
+        assert_eq!(debouncer.samples_remaining(), 3);
+        assert_eq!(debouncer.update(ABState::B), None);
+        assert_eq!(debouncer.samples_remaining(), 2);
+        assert_eq!(debouncer.update(ABState::B), None);
+        assert_eq!(debouncer.samples_remaining(), 1);
+        assert!(debouncer.update(ABState::B).is_some());
+        assert_eq!(debouncer.samples_remaining(), 0);
+
+        // An aborted transition is not in progress anymore
+        assert_eq!(debouncer.update(ABState::A), None);
+        assert_eq!(debouncer.samples_remaining(), 3);
+        assert_eq!(debouncer.update(ABState::B), None);
+        assert_eq!(debouncer.samples_remaining(), 0);
+        assert_eq!(debouncer.update(ABState::A), None);
+        assert_eq!(debouncer.samples_remaining(), 3);
+    }
+
+    #[test]
+    fn test_checked_new() {
+        assert_eq!(
+            Debouncer::<ABState, u8>::checked_new(0, ABState::A),
+            Err(DebouncerError::ZeroThreshold)
+        );
+        assert_eq!(
+            Debouncer::<ABState, u8>::checked_new(1, ABState::A),
+            Ok(Debouncer::new(1, ABState::A))
+        );
+        assert_eq!(
+            Debouncer::<ABState, u16>::checked_new(300, ABState::B),
+            Ok(Debouncer::new(300, ABState::B))
+        );
+    }
+
+    /// Signed counters behave like unsigned ones within their range.
+    #[test]
+    fn test_signed_counter() {
+        let samples = [
+            ABState::B,
+            ABState::B,
+            ABState::A,
+            ABState::B,
+            ABState::B,
+            ABState::B,
+            ABState::A,
+            ABState::A,
+        ];
+        for &threshold in [1, 2, 3, 127].iter() {
+            let mut signed: Debouncer<ABState, i8> = Debouncer::new(threshold, ABState::A);
+            let mut unsigned: Debouncer<ABState, u8> = Debouncer::new(threshold as u8, ABState::A);
+            for &state in samples.iter() {
+                assert_eq!(signed.update(state), unsigned.update(state));
+                assert!(signed.samples_remaining() >= 0);
+            }
+        }
+
+        assert_eq!(
+            Debouncer::<ABState, i8>::checked_new(-1, ABState::A),
+            Err(DebouncerError::ZeroThreshold)
+        );
+        assert!(Debouncer::<ABState, i8>::checked_new(i8::MAX, ABState::A).is_ok());
+    }
+
+    #[cfg(debug_assertions)]
+    #[test]
+    #[should_panic(expected = "threshold must not be zero")]
+    fn test_new_zero_threshold() {
+        let _: Debouncer<ABState, u8> = Debouncer::new(0, ABState::A);
+    }
+
+    #[test]
+    fn test_new_const() {
+        const DEBOUNCER: Debouncer<ABState, u8> = Debouncer::new_const(3, ABState::A);
+        assert_eq!(DEBOUNCER, Debouncer::new(3, ABState::A));
+    }
+
+    #[test]
+    fn test_confidence() {
+        let mut debouncer: Debouncer<ABState, u32> = Debouncer::new(4, ABState::A);
+        assert_eq!(debouncer.confidence(), (ABState::A, 1.0));
+
+        for &expected in [0.25, 0.5, 0.75].iter() {
+            assert_eq!(debouncer.update(ABState::B), None);
+            assert_eq!(debouncer.confidence(), (ABState::B, expected));
+        }
+        assert!(debouncer.update(ABState::B).is_some());
+        assert_eq!(debouncer.confidence(), (ABState::B, 1.0));
+
+        // Drops with an interruption
+        assert_eq!(debouncer.update(ABState::A), None);
+        assert_eq!(debouncer.confidence(), (ABState::A, 0.25));
+        assert_eq!(debouncer.update(ABState::B), None);
+        assert_eq!(debouncer.confidence(), (ABState::B, 1.0));
+    }
+
+    #[test]
+    fn test_snapshot() {
+        let mut debouncer: Debouncer<ABState, u8> = Debouncer::new(4, ABState::A);
+        let expected = DebouncerSnapshot {
+            current_state: ABState::A,
+            next_state: ABState::A,
+            repetition_count: 0,
+            threshold: 4,
+        };
+        assert_eq!(debouncer.snapshot(), expected);
+
+        assert_eq!(debouncer.update(ABState::B), None);
+        assert_eq!(debouncer.update(ABState::B), None);
+        let expected = DebouncerSnapshot {
+            next_state: ABState::B,
+            repetition_count: 2,
+            ..expected
+        };
+        assert_eq!(debouncer.snapshot(), expected);
+
+        assert_eq!(debouncer.update(ABState::B), None);
+        assert!(debouncer.update(ABState::B).is_some());
+        let expected = DebouncerSnapshot {
+            current_state: ABState::B,
+            repetition_count: 0,
+            ..expected
+        };
+        assert_eq!(debouncer.snapshot(), expected);
+    }
+
+    /// A threshold of one behaves like two, and so do the counts reported.
+    #[test]
+    fn test_snapshot_threshold_one() {
+        let mut debouncer: Debouncer<ABState, u8> = Debouncer::new(1, ABState::A);
+        assert_eq!(debouncer.update(ABState::B), None);
+        assert_eq!(
+            debouncer.snapshot(),
+            DebouncerSnapshot {
+                current_state: ABState::A,
+                next_state: ABState::B,
+                repetition_count: 1,
+                threshold: 1,
+            }
+        );
+        assert_eq!(debouncer.confidence(), (ABState::B, 0.5));
+
+        assert!(debouncer.update(ABState::B).is_some());
+        assert_eq!(debouncer.snapshot().repetition_count, 0);
+        assert_eq!(debouncer.confidence(), (ABState::B, 1.0));
+    }
+
+    #[test]
+    fn test_update_status() {
+        let mut debouncer: Debouncer<ABState, u8> = Debouncer::new(3, ABState::A);
+        assert_eq!(debouncer.update_status(ABState::A), UpdateStatus::Stable);
+        assert_eq!(
+            debouncer.update_status(ABState::B),
+            UpdateStatus::Settling { remaining: 2 }
+        );
+        assert_eq!(
+            debouncer.update_status(ABState::B),
+            UpdateStatus::Settling { remaining: 1 }
+        );
+        assert_eq!(
+            debouncer.update_status(ABState::B),
+            UpdateStatus::Changed(Edge::new(ABState::A, ABState::B))
+        );
+        assert_eq!(debouncer.update_status(ABState::B), UpdateStatus::Stable);
+
+        // Returning to the committed state is stable again
+        assert_eq!(
+            debouncer.update_status(ABState::A),
+            UpdateStatus::Settling { remaining: 2 }
+        );
+        assert_eq!(debouncer.update_status(ABState::B), UpdateStatus::Stable);
+    }
+
+    /// Counting up to the maximum of the counter type must not overflow.
+    #[test]
+    fn test_max_threshold() {
+        let mut debouncer: Debouncer<ABState, u8> = Debouncer::new(u8::MAX, ABState::A);
+        for _ in 0..254 {
+            assert_eq!(debouncer.update(ABState::B), None);
+        }
+        assert_eq!(debouncer.samples_remaining(), 1);
+        assert_eq!(
+            debouncer.update(ABState::B),
+            Some(Edge::new(ABState::A, ABState::B))
+        );
+        assert_eq!(debouncer.update(ABState::B), None);
+        assert!(debouncer.is_state(ABState::B));
+    }
+
+    #[test]
+    fn test_set_threshold() {
+        let mut debouncer: Debouncer<ABState, u8> = Debouncer::new(4, ABState::A);
+
+        // Raising keeps the progress
+        assert_eq!(debouncer.update(ABState::B), None);
+        assert_eq!(debouncer.update(ABState::B), None);
+        debouncer.set_threshold(6);
+        assert_eq!(debouncer.samples_remaining(), 4);
+        for _ in 0..3 {
+            assert_eq!(debouncer.update(ABState::B), None);
+        }
+        assert_eq!(
+            debouncer.update(ABState::B),
+            Some(Edge::new(ABState::A, ABState::B))
+        );
+
+        // Lowering below the progress commits on the next confirming sample
+        for _ in 0..4 {
+            assert_eq!(debouncer.update(ABState::A), None);
+        }
+        debouncer.set_threshold(2);
+        assert!(!debouncer.is_state(ABState::B));
+        assert_eq!(
+            debouncer.update(ABState::A),
+            Some(Edge::new(ABState::B, ABState::A))
+        );
+
+        // The new threshold applies to later transitions
+        assert_eq!(debouncer.update(ABState::B), None);
+        assert_eq!(
+            debouncer.update(ABState::B),
+            Some(Edge::new(ABState::A, ABState::B))
+        );
+    }
+
+    #[cfg(debug_assertions)]
+    #[test]
+    #[should_panic(expected = "threshold must not be zero")]
+    fn test_threshold_mut_zero() {
+        let mut debouncer: Debouncer<ABState, u8> = Debouncer::new(3, ABState::A);
+        *debouncer.threshold_mut() = 0;
+    }
+
+    #[test]
+    fn test_threshold_mut_max() {
+        let mut debouncer: Debouncer<ABState, u8> = Debouncer::new(3, ABState::A);
+        assert_eq!(debouncer.update(ABState::B), None);
+        *debouncer.threshold_mut() = u8::MAX;
+        assert_eq!(debouncer.samples_remaining(), u8::MAX - 1);
+        assert_eq!(*debouncer.threshold_mut(), u8::MAX);
+    }
+
+    #[cfg(debug_assertions)]
+    #[test]
+    #[should_panic(expected = "threshold must not be zero")]
+    fn test_set_threshold_zero() {
+        let mut debouncer: Debouncer<ABState, u8> = Debouncer::new(3, ABState::A);
+        debouncer.set_threshold(0);
+    }
+
+    /// The adjustment does not overflow at the ends of the counter range.
+    #[test]
+    fn test_set_threshold_limits() {
+        let mut debouncer: Debouncer<ABState, u8> = Debouncer::new(3, ABState::A);
+        assert_eq!(debouncer.update(ABState::B), None);
+        debouncer.set_threshold(u8::MAX);
+        assert_eq!(debouncer.samples_remaining(), u8::MAX - 1);
+        debouncer.set_threshold(1);
+        assert_eq!(debouncer.samples_remaining(), 1);
+
+        let mut debouncer: Debouncer<ABState, i8> = Debouncer::new(100, ABState::A);
+        assert_eq!(debouncer.update(ABState::B), None);
+        debouncer.set_threshold(i8::MAX);
+        assert_eq!(debouncer.samples_remaining(), i8::MAX - 1);
+        debouncer.set_threshold(2);
+        assert_eq!(debouncer.samples_remaining(), 1);
+        assert_eq!(
+            debouncer.update(ABState::B),
+            Some(Edge::new(ABState::A, ABState::B))
+        );
+    }
+
+    /// Thresholds below two behave like two, so switching between them does
+    /// not change the transition in progress.
+    #[test]
+    fn test_set_threshold_below_two() {
+        let mut debouncer: Debouncer<ABState, u8> = Debouncer::new(1, ABState::A);
+        assert_eq!(debouncer.update(ABState::B), None);
+        debouncer.set_threshold(2);
+        assert_eq!(debouncer.samples_remaining(), 1);
+        assert_eq!(
+            debouncer.update(ABState::B),
+            Some(Edge::new(ABState::A, ABState::B))
+        );
+    }
+
+    #[cfg(debug_assertions)]
+    #[test]
+    #[should_panic(expected = "threshold must not be zero")]
+    fn test_replace_threshold_and_reset_zero() {
+        let mut debouncer: Debouncer<ABState, u8> = Debouncer::new(3, ABState::A);
+        debouncer.replace_threshold_and_reset(0, ABState::B);
+    }
+
+    #[test]
+    fn test_replace_threshold_and_reset() {
+        let mut debouncer: Debouncer<ABState, u8> = Debouncer::new(2, ABState::A);
+        assert_eq!(debouncer.update(ABState::B), None);
+
+        debouncer.replace_threshold_and_reset(4, ABState::B);
+        assert!(debouncer.is_state(ABState::B));
+        assert_eq!(debouncer.samples_remaining(), 0);
+
+        for _ in 0..3 {
+            assert_eq!(debouncer.update(ABState::A), None);
+        }
+        assert_eq!(
+            debouncer.update(ABState::A),
+            Some(Edge::new(ABState::B, ABState::A))
+        );
+    }
+
+    #[test]
+    fn test_eq() {
+        let mut debouncer_01: Debouncer<ABState, u8> = Debouncer::new(3, ABState::A);
+        let mut debouncer_02: Debouncer<ABState, u8> = Debouncer::new(3, ABState::A);
+        assert_eq!(debouncer_01, debouncer_02);
+        assert_ne!(debouncer_01, Debouncer::new(4, ABState::A));
+        assert_ne!(debouncer_01, Debouncer::new(3, ABState::B));
+
+        assert_eq!(debouncer_01.update(ABState::B), None);
+        assert_ne!(debouncer_01, debouncer_02);
+        assert_eq!(debouncer_02.update(ABState::B), None);
+        assert_eq!(debouncer_01, debouncer_02);
+
+        // An aborted transition leaves no trace, unless glitches are counted
+        let mut debouncer_03: Debouncer<ABState, u8> = Debouncer::new(3, ABState::A);
+        assert_eq!(debouncer_01.update(ABState::A), None);
+        assert_eq!(debouncer_03.update(ABState::A), None);
+        #[cfg(not(any(feature = "glitch-count", feature = "edge-latency")))]
+        assert_eq!(debouncer_01, debouncer_03);
+        #[cfg(any(feature = "glitch-count", feature = "edge-latency"))]
+        assert_ne!(debouncer_01, debouncer_03);
+    }
+
+    /// Sampling the committed state discards the count of an aborted
+    /// transition, so the debouncer equals one which never left the state,
+    /// unless glitches or latencies are counted.
+    #[test]
+    fn test_committed_sample_resets_count() {
+        let mut debouncer: Debouncer<ABState, u8> = Debouncer::new(3, ABState::A);
+        assert_eq!(debouncer.update(ABState::B), None);
+        assert_eq!(debouncer.update(ABState::B), None);
+        assert_eq!(debouncer.update(ABState::A), None);
+
+        let mut unchanged: Debouncer<ABState, u8> = Debouncer::new(3, ABState::A);
+        assert_eq!(unchanged.update(ABState::A), None);
+        #[cfg(not(any(feature = "glitch-count", feature = "edge-latency")))]
+        assert_eq!(debouncer, unchanged);
+        #[cfg(any(feature = "glitch-count", feature = "edge-latency"))]
+        assert_ne!(debouncer, unchanged);
+    }
+
+    #[test]
+    fn test_hash() {
+        let mut set = std::collections::HashSet::new();
+        let mut debouncer: Debouncer<ABState, u8> = Debouncer::new(2, ABState::A);
+        assert!(set.insert(Debouncer::new(2, ABState::A)));
+        assert!(!set.insert(Debouncer::new(2, ABState::A)));
+
+        assert_eq!(debouncer.update(ABState::B), None);
+        assert!(!set.contains(&debouncer));
+        assert!(debouncer.update(ABState::B).is_some());
+        assert!(set.insert(debouncer));
+
+        let mut other: Debouncer<ABState, u8> = Debouncer::new(2, ABState::A);
+        assert_eq!(other.update_batch_count(&[ABState::B, ABState::B]), 1);
+        assert!(set.contains(&other));
+    }
+
+    #[test]
+    fn test_with_initial_count() {
+        // Two of four samples towards B
+        let mut debouncer: Debouncer<ABState, u8> =
+            Debouncer::with_initial_count(4, ABState::A, ABState::B, 2).unwrap();
+        assert!(!debouncer.is_state(ABState::A));
+        assert_eq!(debouncer.samples_remaining(), 2);
+        assert_eq!(debouncer.update(ABState::B), None);
+        assert_eq!(
+            debouncer.update(ABState::B),
+            Some(Edge::new(ABState::A, ABState::B))
+        );
+
+        // Three of four samples towards B
+        let mut debouncer: Debouncer<ABState, u8> =
+            Debouncer::with_initial_count(4, ABState::A, ABState::B, 3).unwrap();
+        assert_eq!(
+            debouncer.update(ABState::B),
+            Some(Edge::new(ABState::A, ABState::B))
+        );
+
+        // The pre-seeded count is discarded like any other
+        let mut debouncer: Debouncer<ABState, u8> =
+            Debouncer::with_initial_count(4, ABState::A, ABState::B, 3).unwrap();
+        assert_eq!(debouncer.update(ABState::A), None);
+        assert_eq!(debouncer.update(ABState::B), None);
+        assert_eq!(debouncer.samples_remaining(), 3);
+
+        // No transition towards the current state
+        assert_eq!(
+            Debouncer::with_initial_count(4, ABState::A, ABState::A, 1),
+            Some(Debouncer::new(4, ABState::A))
+        );
+
+        // A threshold of one counts like two
+        assert_eq!(
+            Debouncer::with_initial_count(1, ABState::A, ABState::B, 0),
+            Some(Debouncer::new(1, ABState::A))
+        );
+        let mut debouncer: Debouncer<ABState, u8> =
+            Debouncer::with_initial_count(1, ABState::A, ABState::B, 1).unwrap();
+        assert_eq!(debouncer.samples_remaining(), 1);
+        assert_eq!(
+            debouncer.update(ABState::B),
+            Some(Edge::new(ABState::A, ABState::B))
+        );
+
+        assert_eq!(
+            Debouncer::<ABState, u8>::with_initial_count(4, ABState::A, ABState::B, 5),
+            None
+        );
+        assert_eq!(
+            Debouncer::<ABState, u8>::with_initial_count(0, ABState::A, ABState::B, 0),
+            None
+        );
+    }
+
+    #[test]
+    fn test_primed() {
+        let mut debouncer: Debouncer<ABState, u8> = Debouncer::primed(8, ABState::A, ABState::B);
+        assert!(debouncer.is_settling());
+        assert_eq!(
+            debouncer.peek_update(ABState::B),
+            Some(Edge::new(ABState::A, ABState::B))
+        );
+        assert_eq!(
+            debouncer.update(ABState::B),
+            Some(Edge::new(ABState::A, ABState::B))
+        );
+
+        let debouncer: Debouncer<ABState, u8> = Debouncer::primed(8, ABState::A, ABState::A);
+        assert_eq!(debouncer, Debouncer::new(8, ABState::A));
+    }
+
+    #[test]
+    fn test_reset() {
+        let mut debouncer: Debouncer<ABState, u8> = Debouncer::new(3, ABState::A);
+        assert_eq!(debouncer.update(ABState::B), None);
+        debouncer.reset(ABState::B);
+        assert_eq!(debouncer, Debouncer::new(3, ABState::B));
+
+        assert_eq!(debouncer.update(ABState::A), None);
+        debouncer.reset(ABState::B);
+        assert_eq!(debouncer, Debouncer::new(3, ABState::B));
+    }
+
+    #[test]
+    fn test_reset_to_current() {
+        let mut debouncer: Debouncer<ABState, u8> = Debouncer::new(3, ABState::A);
+        assert_eq!(debouncer.update(ABState::B), None);
+        assert_eq!(debouncer.update(ABState::B), None);
+
+        debouncer.reset_to_current();
+        assert!(debouncer.is_state(ABState::A));
+        assert_eq!(debouncer.samples_remaining(), 0);
+
+        // The transition restarts from scratch
+        assert_eq!(debouncer.update(ABState::B), None);
+        assert_eq!(debouncer.update(ABState::B), None);
+        assert_eq!(
+            debouncer.update(ABState::B),
+            Some(Edge::new(ABState::A, ABState::B))
+        );
+    }
+
+    #[test]
+    fn test_force_state() {
+        let mut debouncer: Debouncer<ABState, u8> = Debouncer::new(3, ABState::A);
+
+        // Forcing the committed state discards the transition silently
+        assert_eq!(debouncer.update(ABState::B), None);
+        assert_eq!(debouncer.update(ABState::B), None);
+        assert_eq!(debouncer.force_state(ABState::A), None);
+        assert!(debouncer.is_state(ABState::A));
+        assert_eq!(debouncer.update(ABState::B), None);
+        assert_eq!(debouncer.update(ABState::B), None);
+
+        // Forcing another state reports the edge
+        assert_eq!(
+            debouncer.force_state(ABState::B),
+            Some(Edge::new(ABState::A, ABState::B))
+        );
+        assert!(debouncer.is_state(ABState::B));
+        assert_eq!(debouncer.update(ABState::B), None);
     }
-}
 
-#[derive(Debug)]
-pub struct Debouncer<T, S> {
-    current_state: T,
-    next_state: T,
-    repetition_count: S,
-    threshold: S,
-}
+    /// A source replaying a fixed sequence of samples.
+    struct MockSource<'a> {
+        samples: core::slice::Iter<'a, ABState>,
+    }
 
-impl<T, S> Debouncer<T, S>
-where
-    T: PartialEq + Copy,
-    S: num::traits::One + core::ops::Add<Output = S> + PartialEq + PartialOrd + Copy,
-{
-    pub fn new(threshold: S, inital_state: T) -> Self {
-        Debouncer {
-            current_state: inital_state,
-            next_state: inital_state,
-            repetition_count: threshold,
-            threshold: threshold,
+    impl DebounceSource for MockSource<'_> {
+        type State = ABState;
+
+        fn sample(&mut self) -> ABState {
+            *self.samples.next().expect("script exhausted")
         }
     }
 
-    pub fn update(&mut self, state: T) -> Option<Edge<T>> {
-        if self.current_state == state {
-            self.current_state = self.current_state;
-            self.next_state = state;
-            self.repetition_count = self.repetition_count;
+    #[test]
+    fn test_poll_source() {
+        let mut source = MockSource {
+            samples: [ABState::B, ABState::A, ABState::B, ABState::B].iter(),
+        };
+        let mut debouncer: Debouncer<ABState, u8> = Debouncer::new(2, ABState::A);
 
-            None
-        } else if self.current_state != state && self.next_state != state {
-            self.current_state = self.current_state;
-            self.next_state = state;
-            self.repetition_count = S::one();
+        assert_eq!(debouncer.poll_source(&mut source), None);
+        assert_eq!(debouncer.poll_source(&mut source), None);
+        assert_eq!(debouncer.poll_source(&mut source), None);
+        assert_eq!(
+            debouncer.poll_source(&mut source),
+            Some(Edge::new(ABState::A, ABState::B))
+        );
+    }
 
-            None
-        } else if self.current_state != state
-            && self.next_state == state
-            && self.repetition_count + S::one() < self.threshold
-        {
-            self.current_state = self.current_state;
-            self.next_state = state;
-            self.repetition_count = self.repetition_count + S::one();
+    #[test]
+    fn test_is_stable_settling() {
+        let mut debouncer: Debouncer<ABState, u8> = Debouncer::new(3, ABState::A);
+        assert!(debouncer.is_stable());
+        assert!(!debouncer.is_settling());
 
-            None
-        } else if self.current_state != state
-            && self.next_state == state
-            && self.repetition_count + S::one() >= self.threshold
-        {
-            let from_state = self.current_state;
-            let to_state = self.next_state;
+        assert_eq!(debouncer.update(ABState::B), None);
+        assert!(debouncer.is_settling());
+        assert!(!debouncer.is_stable());
+        assert_eq!(debouncer.update(ABState::B), None);
+        assert!(debouncer.is_settling());
 
-            self.current_state = state;
-            self.next_state = state;
-            self.repetition_count = self.threshold;
+        // Settled by an edge
+        assert!(debouncer.update(ABState::B).is_some());
+        assert!(debouncer.is_stable());
 
-            Some(Edge::new(from_state, to_state))
-        } else {
-            // Only so that the compiler does not complain
-            None
-        }
+        // Settled by returning to the committed state
+        assert_eq!(debouncer.update(ABState::A), None);
+        assert!(debouncer.is_settling());
+        assert_eq!(debouncer.update(ABState::B), None);
+        assert!(debouncer.is_stable());
+        assert!(!debouncer.is_settling());
     }
 
-    pub fn is_state(&self, state: T) -> bool {
-        self.current_state == self.next_state && self.current_state == state
+    #[test]
+    fn test_is_state_or_settling_to() {
+        let mut debouncer: Debouncer<ABCState, u8> = Debouncer::new(3, ABCState::A);
+        assert!(debouncer.is_state_or_settling_to(ABCState::A));
+        assert!(!debouncer.is_state_or_settling_to(ABCState::B));
+
+        // Settling towards B
+        assert_eq!(debouncer.update(ABCState::B), None);
+        assert!(debouncer.is_state_or_settling_to(ABCState::A));
+        assert!(debouncer.is_state_or_settling_to(ABCState::B));
+        assert!(!debouncer.is_state_or_settling_to(ABCState::C));
+
+        // Settling away from B
+        assert_eq!(debouncer.update(ABCState::B), None);
+        assert!(debouncer.update(ABCState::B).is_some());
+        assert_eq!(debouncer.update(ABCState::C), None);
+        assert!(!debouncer.is_state_or_settling_to(ABCState::A));
+        assert!(debouncer.is_state_or_settling_to(ABCState::B));
+        assert!(debouncer.is_state_or_settling_to(ABCState::C));
     }
-}
 
-#[cfg(test)]
-mod tests {
-    use super::*;
+    /// A single sample of the committed state restarts the count.
+    #[test]
+    fn test_interrupted_run_restarts() {
+        let mut debouncer: Debouncer<ABState, u8> = Debouncer::new(4, ABState::A);
+        let noisy = [ABState::B, ABState::B, ABState::B, ABState::A, ABState::B];
+        assert_eq!(debouncer.update_batch(&noisy), None);
+        assert_eq!(debouncer.samples_remaining(), 3);
 
-    #[allow(dead_code)]
-    #[derive(Debug, PartialEq, Clone, Copy)]
-    enum ABState {
-        A,
-        B,
+        assert_eq!(debouncer.update(ABState::B), None);
+        assert_eq!(debouncer.update(ABState::B), None);
+        assert_eq!(
+            debouncer.update(ABState::B),
+            Some(Edge::new(ABState::A, ABState::B))
+        );
     }
 
-    #[derive(Debug)]
-    struct ABDebouncer {
-        inner: Debouncer<ABState, u8>,
+    #[test]
+    fn test_update_if_changed() {
+        let mut debouncer: Debouncer<ABState, u8> = Debouncer::new(3, ABState::A);
+        assert!(!debouncer.update_if_changed(ABState::A));
+        assert!(!debouncer.update_if_changed(ABState::B));
+        assert!(!debouncer.update_if_changed(ABState::B));
+        assert!(debouncer.update_if_changed(ABState::B));
+        assert!(!debouncer.update_if_changed(ABState::B));
+        assert!(debouncer.is_state(ABState::B));
     }
-    
-    impl ABDebouncer {
-        fn new(threshold: u8, inital_state: ABState) -> Self {
-            ABDebouncer {
-                inner: Debouncer::new(threshold, inital_state),
+
+    #[test]
+    fn test_observe() {
+        let mut trace = std::vec::Vec::new();
+        let mut sink = |state, edge| trace.push((state, edge));
+
+        let mut debouncer: Debouncer<ABState, u8> = Debouncer::new(2, ABState::A);
+        for &state in [ABState::A, ABState::B, ABState::B].iter() {
+            let _ = debouncer.observe(state, &mut sink);
+        }
+
+        assert_eq!(
+            trace,
+            [
+                (ABState::A, None),
+                (ABState::B, None),
+                (ABState::B, Some(Edge::new(ABState::A, ABState::B))),
+            ]
+        );
+    }
+
+    /// The same samples always yield the same results, and the committed
+    /// state leaves a stable debouncer unchanged.
+    #[test]
+    fn test_update_deterministic() {
+        use crate::pattern::BouncePattern;
+        use crate::pin::PinState;
+
+        let mut first: Debouncer<PinState, u8> = Debouncer::new(3, PinState::Low);
+        let mut second = first;
+        for sample in BouncePattern::new(0x5eed, 64, PinState::Low).take(1000) {
+            assert_eq!(first.update_status(sample), second.update_status(sample));
+            assert_eq!(first, second);
+        }
+
+        let mut debouncer: Debouncer<ABState, u8> = Debouncer::new(3, ABState::A);
+        let original = debouncer;
+        for _ in 0..3 {
+            assert_eq!(debouncer.update_status(ABState::A), UpdateStatus::Stable);
+        }
+        #[cfg(not(any(feature = "stable-count", feature = "settled-samples")))]
+        assert_eq!(debouncer, original);
+        #[cfg(any(feature = "stable-count", feature = "settled-samples"))]
+        assert!(debouncer.is_state(original.current_state));
+    }
+
+    #[cfg(feature = "log")]
+    #[test]
+    fn test_log_trace() {
+        use std::string::{String, ToString};
+        use std::sync::Mutex;
+        use std::thread::{self, ThreadId};
+        use std::vec::Vec;
+
+        // Other tests run in parallel, so records are kept per thread
+        struct CapturingLogger(Mutex<Vec<(ThreadId, String)>>);
+
+        impl log::Log for CapturingLogger {
+            fn enabled(&self, metadata: &log::Metadata) -> bool {
+                metadata.level() <= log::Level::Trace
+            }
+
+            fn log(&self, record: &log::Record) {
+                let message = record.args().to_string();
+                self.0
+                    .lock()
+                    .unwrap()
+                    .push((thread::current().id(), message));
             }
+
+            fn flush(&self) {}
         }
-    
-        fn update(&mut self, state: ABState) -> Option<Edge<ABState>> {
-            self.inner.update(state)
+
+        static LOGGER: CapturingLogger = CapturingLogger(Mutex::new(Vec::new()));
+        log::set_logger(&LOGGER).unwrap();
+        log::set_max_level(log::LevelFilter::Trace);
+
+        let records = || -> Vec<String> {
+            LOGGER
+                .0
+                .lock()
+                .unwrap()
+                .iter()
+                .filter(|(id, _)| *id == thread::current().id())
+                .map(|(_, message)| message.clone())
+                .collect()
+        };
+
+        let mut debouncer: Debouncer<ABState, u8> = Debouncer::new(2, ABState::A);
+        assert_eq!(debouncer.update(ABState::B), None);
+        assert_eq!(debouncer.update(ABState::A), None);
+        assert!(records().is_empty());
+
+        assert_eq!(debouncer.update(ABState::B), None);
+        assert_eq!(
+            debouncer.update(ABState::B),
+            Some(Edge::new(ABState::A, ABState::B))
+        );
+        assert_eq!(records(), ["debounced an edge"]);
+
+        assert_eq!(debouncer.update(ABState::B), None);
+        assert_eq!(debouncer.update(ABState::A), None);
+        assert_eq!(
+            debouncer.update(ABState::A),
+            Some(Edge::new(ABState::B, ABState::A))
+        );
+        assert_eq!(records(), ["debounced an edge", "debounced an edge"]);
+
+        // Peeking at an edge does not trace
+        assert_eq!(debouncer.update(ABState::B), None);
+        assert_eq!(
+            debouncer.peek_update(ABState::B),
+            Some(Edge::new(ABState::A, ABState::B))
+        );
+        assert_eq!(records().len(), 2);
+    }
+
+    #[test]
+    fn test_threshold_mut() {
+        let mut debouncer: Debouncer<ABState, u8> = Debouncer::new(6, ABState::A);
+        for _ in 0..3 {
+            assert_eq!(debouncer.update(ABState::B), None);
         }
-    
-        fn is_a(&self) -> bool {
-            self.inner.is_state(ABState::A)
+
+        // Shrinking below the count commits on the next confirming sample
+        *debouncer.threshold_mut() = 2;
+        assert_eq!(*debouncer.threshold_mut(), 2);
+        assert!(!debouncer.is_state(ABState::B));
+        assert_eq!(
+            debouncer.update(ABState::B),
+            Some(Edge::new(ABState::A, ABState::B))
+        );
+
+        // Adjusting in several steps only counts the final threshold
+        assert_eq!(debouncer.update(ABState::A), None);
+        {
+            let mut threshold = debouncer.threshold_mut();
+            *threshold = 1;
+            *threshold += 3;
         }
-    
-        fn is_b(&self) -> bool {
-            self.inner.is_state(ABState::B)
+        assert_eq!(debouncer.samples_remaining(), 3);
+        assert_eq!(debouncer.update(ABState::A), None);
+        assert_eq!(debouncer.update(ABState::A), None);
+        assert_eq!(
+            debouncer.update(ABState::A),
+            Some(Edge::new(ABState::B, ABState::A))
+        );
+
+        // A leaked guard leaves the count, which the next sample caps
+        assert_eq!(debouncer.update(ABState::B), None);
+        let mut threshold = debouncer.threshold_mut();
+        *threshold = 2;
+        core::mem::forget(threshold);
+        assert_eq!(debouncer.samples_remaining(), 3);
+        assert_eq!(debouncer.update(ABState::B), None);
+        assert_eq!(debouncer.samples_remaining(), 1);
+        assert_eq!(
+            debouncer.update(ABState::B),
+            Some(Edge::new(ABState::A, ABState::B))
+        );
+    }
+
+    #[cfg(feature = "alloc")]
+    #[test]
+    fn test_replay() {
+        use ABCState::{A, B, C};
+
+        let samples = [B, B, C, A, C, C, C, B];
+        let (debouncer, edges) = Debouncer::<ABCState, u8>::replay(2, A, samples.iter().copied());
+        assert_eq!(edges, [Edge::new(A, B), Edge::new(B, C)]);
+        assert!(debouncer.is_settling());
+        assert_eq!(debouncer.snapshot().current_state, C);
+        assert_eq!(debouncer.snapshot().next_state, B);
+
+        let (debouncer, edges) = Debouncer::<ABCState, u8>::replay(2, A, core::iter::empty());
+        assert!(edges.is_empty());
+        assert!(debouncer.is_state(A));
+    }
+
+    #[test]
+    fn test_map_counter() {
+        let mut debouncer: Debouncer<ABState, u32> = Debouncer::new(4, ABState::A);
+        assert_eq!(debouncer.update(ABState::B), None);
+        assert_eq!(debouncer.update(ABState::B), None);
+
+        let mut debouncer: Debouncer<ABState, u8> =
+            debouncer.map_counter(|count| count.min(u32::from(u8::MAX)) as u8);
+        assert_eq!(debouncer.samples_remaining(), 2);
+        assert_eq!(debouncer.update(ABState::B), None);
+        assert_eq!(
+            debouncer.update(ABState::B),
+            Some(Edge::new(ABState::A, ABState::B))
+        );
+
+        // The threshold carries over as well
+        assert_eq!(debouncer.update(ABState::A), None);
+        assert_eq!(debouncer.update(ABState::A), None);
+        assert_eq!(debouncer.update(ABState::A), None);
+        assert_eq!(
+            debouncer.update(ABState::A),
+            Some(Edge::new(ABState::B, ABState::A))
+        );
+    }
+
+    #[test]
+    fn test_peek_update() {
+        let mut debouncer: Debouncer<ABState, u8> = Debouncer::new(2, ABState::A);
+        let original = debouncer;
+
+        assert_eq!(debouncer.peek_update(ABState::B), None);
+        assert_eq!(debouncer.peek_update(ABState::A), None);
+        assert_eq!(debouncer, original);
+
+        assert_eq!(debouncer.update(ABState::B), None);
+        for _ in 0..3 {
+            assert_eq!(
+                debouncer.peek_update(ABState::B),
+                Some(Edge::new(ABState::A, ABState::B))
+            );
+            assert!(!debouncer.is_state(ABState::B));
         }
+        assert_eq!(
+            debouncer.update(ABState::B),
+            Some(Edge::new(ABState::A, ABState::B))
+        );
     }
 
     #[test]
@@ -132,7 +2179,7 @@ mod tests {
         // Further highs do not indicate a rising edge anymore
         assert_eq!(debouncer.update(ABState::B), None);
 
-        // A low state does not reset counting
+        // A single low sample is discarded, so no second rising edge follows
         assert_eq!(debouncer.update(ABState::A), None);
         assert_eq!(debouncer.update(ABState::B), None);
         assert_eq!(debouncer.update(ABState::B), None);
@@ -159,7 +2206,7 @@ mod tests {
         // Further lowss do not indicate a rising edge anymore
         assert_eq!(debouncer.update(ABState::A), None);
 
-        // A high state does not reset counting
+        // A single high sample is discarded, so no second falling edge follows
         assert_eq!(debouncer.update(ABState::B), None);
         assert_eq!(debouncer.update(ABState::A), None);
         assert_eq!(debouncer.update(ABState::A), None);
@@ -195,7 +2242,7 @@ mod tests {
         assert!(!debouncer.is_b());
 
         // Depressed updates don't change the situation
-        debouncer.update(ABState::A);
+        assert_eq!(debouncer.update(ABState::A), None);
         assert!(debouncer.is_a());
         assert!(!debouncer.is_b());
 
@@ -220,28 +2267,240 @@ mod tests {
         assert!(debouncer.is_b());
     }
 
-    /// Ensure the promised low RAM consumption.
+    #[cfg(feature = "edge-count")]
     #[test]
-    fn test_ram_consumption() {
-        // Regular debouncers
+    fn test_edge_count() {
+        let mut debouncer: Debouncer<ABState, u8> = Debouncer::new(2, ABState::A);
+        assert_eq!(debouncer.edge_count(), 0);
+
+        let samples = [
+            ABState::B,
+            ABState::B,
+            ABState::A,
+            ABState::B,
+            ABState::A,
+            ABState::A,
+            ABState::B,
+            ABState::B,
+        ];
+        assert_eq!(debouncer.update_batch_count(&samples), 3);
+        assert_eq!(debouncer.edge_count(), 3);
         assert_eq!(
-            std::mem::size_of_val(&ABDebouncer::new(2, ABState::A)),
-            4
+            debouncer.force_state(ABState::A),
+            Some(Edge::new(ABState::B, ABState::A))
         );
+        assert_eq!(debouncer.edge_count(), 3);
+
+        debouncer.reset_edge_count();
+        assert_eq!(debouncer.edge_count(), 0);
+        assert_eq!(debouncer.update_batch_count(&samples), 3);
+        assert_eq!(debouncer.edge_count(), 3);
+    }
+
+    #[cfg(feature = "stable-count")]
+    #[test]
+    fn test_stable_for() {
+        let mut debouncer: Debouncer<ABState, u8> = Debouncer::new(2, ABState::A);
+        assert_eq!(debouncer.stable_for(), 0);
+
+        for count in 1..=100 {
+            assert_eq!(debouncer.update(ABState::A), None);
+            assert_eq!(debouncer.stable_for(), count);
+        }
+        assert!(debouncer.is_stuck(100));
+        assert!(!debouncer.is_stuck(101));
+
+        // A single other sample restarts the count
+        assert_eq!(debouncer.update(ABState::B), None);
+        assert_eq!(debouncer.stable_for(), 0);
+        assert_eq!(debouncer.update(ABState::A), None);
+        assert_eq!(debouncer.stable_for(), 1);
+
+        // So does an edge
+        assert_eq!(debouncer.update(ABState::B), None);
+        assert!(debouncer.update(ABState::B).is_some());
+        assert_eq!(debouncer.stable_for(), 0);
+        assert_eq!(debouncer.update(ABState::B), None);
+        assert_eq!(debouncer.stable_for(), 1);
+
+        // Long runs saturate
+        for _ in 0..300 {
+            assert_eq!(debouncer.update(ABState::B), None);
+        }
+        assert_eq!(debouncer.stable_for(), u8::MAX);
+        assert!(debouncer.is_stuck(u8::MAX));
+    }
+
+    #[cfg(feature = "glitch-count")]
+    #[test]
+    fn test_glitch_count() {
+        let mut debouncer: Debouncer<ABCState, u8> = Debouncer::new(3, ABCState::A);
+        assert_eq!(debouncer.glitch_count(), 0);
+
+        // Aborted by the committed state
+        let samples = [
+            ABCState::B,
+            ABCState::B,
+            ABCState::A,
+            ABCState::B,
+            ABCState::A,
+        ];
+        assert_eq!(debouncer.update_batch(&samples), None);
+        assert_eq!(debouncer.glitch_count(), 2);
+
+        // Aborted by another candidate, then committed
+        let samples = [ABCState::B, ABCState::C, ABCState::C, ABCState::C];
+        assert!(debouncer.update_batch(&samples).is_some());
+        assert_eq!(debouncer.glitch_count(), 3);
+
+        // Stable samples and edges are no glitches
+        let samples = [ABCState::C, ABCState::A, ABCState::A, ABCState::A];
+        assert!(debouncer.update_batch(&samples).is_some());
+        assert_eq!(debouncer.glitch_count(), 3);
+
+        debouncer.reset_glitch_count();
+        assert_eq!(debouncer.glitch_count(), 0);
+        assert_eq!(debouncer.update(ABCState::B), None);
+        debouncer.reset(ABCState::A);
+        assert_eq!(debouncer.glitch_count(), 0);
+    }
+
+    #[cfg(feature = "edge-latency")]
+    #[test]
+    fn test_last_edge_latency() {
+        let mut debouncer: Debouncer<ABState, u8> = Debouncer::new(3, ABState::A);
+        assert_eq!(debouncer.last_edge_latency(), 0);
+
+        // Clean transition
+        let samples = [ABState::A, ABState::B, ABState::B, ABState::B];
+        assert!(debouncer.update_batch(&samples).is_some());
+        assert_eq!(debouncer.last_edge_latency(), 3);
+
+        // Noisy transition
+        let samples = [
+            ABState::B,
+            ABState::A,
+            ABState::B,
+            ABState::A,
+            ABState::A,
+            ABState::A,
+        ];
+        assert!(debouncer.update_batch(&samples).is_some());
+        assert_eq!(debouncer.last_edge_latency(), 5);
+
+        // Kept until the next edge
+        assert_eq!(debouncer.update(ABState::B), None);
+        assert_eq!(debouncer.last_edge_latency(), 5);
+        debouncer.reset(ABState::A);
+        assert_eq!(debouncer.last_edge_latency(), 5);
+        assert_eq!(debouncer.update_batch_count(&[ABState::B; 3]), 1);
+        assert_eq!(debouncer.last_edge_latency(), 3);
+    }
+
+    #[cfg(feature = "last-edge")]
+    #[test]
+    fn test_last_edge() {
+        let mut debouncer: Debouncer<ABState, u8> = Debouncer::new(2, ABState::A);
+        assert_eq!(debouncer.last_edge(), None);
+        assert_eq!(debouncer.update(ABState::B), None);
+        assert_eq!(debouncer.last_edge(), None);
+
+        assert!(debouncer.update(ABState::B).is_some());
         assert_eq!(
-            std::mem::size_of_val(&ABDebouncer::new(8, ABState::A)),
-            4
+            debouncer.last_edge(),
+            Some(Edge::new(ABState::A, ABState::B))
         );
+
+        // Kept across updates without an edge
+        let samples = [ABState::B, ABState::A, ABState::B, ABState::A];
+        assert_eq!(debouncer.update_batch(&samples), None);
         assert_eq!(
-            std::mem::size_of_val(&ABDebouncer::new(9, ABState::A)),
-            4
+            debouncer.last_edge(),
+            Some(Edge::new(ABState::A, ABState::B))
         );
+
+        assert!(debouncer.update(ABState::A).is_some());
         assert_eq!(
-            std::mem::size_of_val(&ABDebouncer::new(16, ABState::A)),
-            4
+            debouncer.last_edge(),
+            Some(Edge::new(ABState::B, ABState::A))
         );
     }
 
+    #[cfg(feature = "settled-samples")]
+    #[test]
+    fn test_settled_samples() {
+        let mut debouncer: Debouncer<ABState, u8> = Debouncer::new(2, ABState::A);
+        assert_eq!(debouncer.settled_samples(), 0);
+
+        let samples = [ABState::A, ABState::A, ABState::B, ABState::A];
+        assert_eq!(debouncer.update_batch(&samples), None);
+        assert_eq!(debouncer.settled_samples(), 3);
+
+        // Reset with each edge
+        assert_eq!(debouncer.update(ABState::B), None);
+        assert!(debouncer.update(ABState::B).is_some());
+        assert_eq!(debouncer.settled_samples(), 0);
+        assert_eq!(debouncer.update(ABState::B), None);
+        assert_eq!(debouncer.settled_samples(), 1);
+
+        for _ in 0..300 {
+            assert_eq!(debouncer.update(ABState::B), None);
+        }
+        assert_eq!(debouncer.settled_samples(), u8::MAX);
+    }
+
+    cfg_no_optional_counters! {
+        /// Ensure the promised low RAM consumption.
+        #[test]
+        fn test_ram_consumption() {
+            // Regular debouncers
+            assert_eq!(
+                std::mem::size_of_val(&ABDebouncer::new(2, ABState::A)),
+                4
+            );
+            assert_eq!(
+                std::mem::size_of_val(&ABDebouncer::new(8, ABState::A)),
+                4
+            );
+            assert_eq!(
+                std::mem::size_of_val(&ABDebouncer::new(9, ABState::A)),
+                4
+            );
+            assert_eq!(
+                std::mem::size_of_val(&ABDebouncer::new(16, ABState::A)),
+                4
+            );
+        }
+    }
+
+    cfg_no_optional_counters! {
+        /// Ensure the RAM consumption across counter and state types.
+        ///
+        /// A debouncer stores two states and two counters. The fields are
+        /// reordered by the compiler, so the only padding is to the alignment of
+        /// the counter: 2 + 2 * 2 = 6 for `u16` and 2 + 2 + 2 * 4 = 12 for `u32`.
+        #[test]
+        fn test_ram_consumption_counter_types() {
+            #[allow(dead_code)]
+            #[derive(PartialEq, Clone, Copy)]
+            #[repr(u8)]
+            enum Level {
+                Low,
+                Mid,
+                High,
+            }
+
+            assert_eq!(std::mem::size_of::<Debouncer<ABState, u8>>(), 4);
+            assert_eq!(std::mem::size_of::<Debouncer<ABState, u16>>(), 6);
+            assert_eq!(std::mem::size_of::<Debouncer<ABState, u32>>(), 12);
+
+            assert_eq!(std::mem::size_of::<Level>(), 1);
+            assert_eq!(std::mem::size_of::<Debouncer<Level, u8>>(), 4);
+            assert_eq!(std::mem::size_of::<Debouncer<Level, u16>>(), 6);
+            assert_eq!(std::mem::size_of::<Debouncer<Level, u32>>(), 12);
+        }
+    }
+
     /// Ensure that the initial state can be specified.
     #[test]
     fn test_initial_state() {
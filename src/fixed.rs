@@ -0,0 +1,117 @@
+#![deny(unsafe_code)]
+
+use super::debouncer::{countdown, Countdown, Edge};
+
+/// A debouncer with the threshold fixed at compile time.
+///
+/// It behaves like `Debouncer<T, u8>` with the same threshold, but does not
+/// need to store the threshold.
+///
+/// The samples still needed are counted in a single byte, so that e.g.
+/// `ConstDebouncer<PinState, 8>` takes 3 bytes. A threshold above 255 fails
+/// the build.
+#[derive(Debug, PartialEq, Eq, Hash, Clone, Copy)]
+pub struct ConstDebouncer<T, const THRESHOLD: usize> {
+    current_state: T,
+    next_state: T,
+    remaining: u8,
+}
+
+impl<T, const THRESHOLD: usize> ConstDebouncer<T, THRESHOLD>
+where
+    T: PartialEq + Copy,
+{
+    // Evaluated when referenced, failing the build if the counter cannot hold
+    // the threshold
+    const VALID_THRESHOLD: () = assert!(THRESHOLD <= 255, "the threshold must not exceed 255");
+
+    pub fn new(inital_state: T) -> Self {
+        let () = Self::VALID_THRESHOLD;
+
+        ConstDebouncer {
+            current_state: inital_state,
+            next_state: inital_state,
+            remaining: 0,
+        }
+    }
+
+    #[must_use = "a detected edge should be handled"]
+    pub fn update(&mut self, state: T) -> Option<Edge<T>> {
+        let is_current = self.current_state == state;
+        let is_next = self.next_state == state;
+
+        match countdown(&mut self.remaining, THRESHOLD as u8, is_current, is_next) {
+            Countdown::Stable | Countdown::Started => {
+                self.next_state = state;
+
+                None
+            }
+            Countdown::Continued => None,
+            Countdown::Committed => {
+                let from_state = self.current_state;
+                self.current_state = state;
+
+                Some(Edge::new(from_state, state))
+            }
+        }
+    }
+
+    pub fn is_state(&self, state: T) -> bool {
+        self.current_state == self.next_state && self.current_state == state
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::debouncer::Debouncer;
+    use crate::pin::PinState::{self, High, Low};
+
+    #[test]
+    fn test_update() {
+        let mut debouncer: ConstDebouncer<PinState, 3> = ConstDebouncer::new(Low);
+        assert!(debouncer.is_state(Low));
+
+        assert_eq!(debouncer.update(High), None);
+        assert_eq!(debouncer.update(High), None);
+        assert!(!debouncer.is_state(High));
+        assert_eq!(debouncer.update(High), Some(Edge::new(Low, High)));
+        assert!(debouncer.is_state(High));
+
+        assert_eq!(debouncer.update(Low), None);
+        assert_eq!(debouncer.update(High), None);
+        assert_eq!(debouncer.update(Low), None);
+        assert_eq!(debouncer.update(Low), None);
+        assert_eq!(debouncer.update(Low), Some(Edge::new(High, Low)));
+    }
+
+    /// Ensure the same edges as the runtime debouncer.
+    #[test]
+    fn test_same_as_runtime() {
+        let samples = [
+            High, High, Low, High, Low, Low, Low, High, High, High, High, Low, High, Low, Low,
+        ];
+
+        let mut fixed: ConstDebouncer<PinState, 2> = ConstDebouncer::new(Low);
+        let mut runtime: Debouncer<PinState, u8> = Debouncer::new(2, Low);
+        for &state in samples.iter() {
+            assert_eq!(fixed.update(state), runtime.update(state));
+        }
+
+        let mut fixed: ConstDebouncer<PinState, 1> = ConstDebouncer::new(Low);
+        let mut runtime: Debouncer<PinState, u8> = Debouncer::new(1, Low);
+        for &state in samples.iter() {
+            assert_eq!(fixed.update(state), runtime.update(state));
+        }
+    }
+
+    /// Ensure the fixed threshold saves RAM.
+    #[test]
+    fn test_ram_consumption() {
+        assert_eq!(std::mem::size_of::<ConstDebouncer<PinState, 8>>(), 3);
+        assert!(
+            std::mem::size_of::<ConstDebouncer<PinState, 8>>()
+                < std::mem::size_of::<Debouncer<PinState, u8>>()
+        );
+    }
+}
@@ -0,0 +1,212 @@
+#![deny(unsafe_code)]
+
+use embedded_hal::digital::InputPin;
+
+use super::debouncer::Edge;
+use super::pin::{PinState, SmallPinDebouncer};
+
+/// An `embedded-hal` input pin which is debounced on every `poll`.
+#[derive(Debug)]
+pub struct DebouncedInputPin<P> {
+    pin: P,
+    debouncer: SmallPinDebouncer,
+}
+
+impl<P> DebouncedInputPin<P>
+where
+    P: InputPin,
+{
+    pub fn new(pin: P, threshold: u8, inital_state: PinState) -> Self {
+        DebouncedInputPin {
+            pin,
+            debouncer: SmallPinDebouncer::new(threshold, inital_state),
+        }
+    }
+
+    /// Reads the pin once and feeds the level into the debouncer.
+    #[must_use = "a detected edge should be handled"]
+    pub fn poll(&mut self) -> Result<Option<Edge<PinState>>, P::Error> {
+        let state = if self.pin.is_high()? {
+            PinState::High
+        } else {
+            PinState::Low
+        };
+
+        Ok(self.debouncer.update(state))
+    }
+
+    pub fn is_high(&self) -> bool {
+        self.debouncer.is_high()
+    }
+
+    pub fn is_low(&self) -> bool {
+        self.debouncer.is_low()
+    }
+
+    /// Returns the wrapped pin.
+    pub fn release(self) -> P {
+        self.pin
+    }
+}
+
+impl From<embedded_hal::digital::PinState> for PinState {
+    fn from(state: embedded_hal::digital::PinState) -> Self {
+        match state {
+            embedded_hal::digital::PinState::Low => PinState::Low,
+            embedded_hal::digital::PinState::High => PinState::High,
+        }
+    }
+}
+
+impl From<PinState> for embedded_hal::digital::PinState {
+    fn from(state: PinState) -> Self {
+        match state {
+            PinState::Low => embedded_hal::digital::PinState::Low,
+            PinState::High => embedded_hal::digital::PinState::High,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use core::convert::Infallible;
+    use embedded_hal::digital::ErrorType;
+
+    /// A fake pin returning a scripted sequence of levels.
+    struct MockPin<'a> {
+        levels: core::slice::Iter<'a, bool>,
+    }
+
+    impl<'a> MockPin<'a> {
+        fn new(levels: &'a [bool]) -> Self {
+            MockPin {
+                levels: levels.iter(),
+            }
+        }
+    }
+
+    impl ErrorType for MockPin<'_> {
+        type Error = Infallible;
+    }
+
+    impl InputPin for MockPin<'_> {
+        fn is_high(&mut self) -> Result<bool, Self::Error> {
+            Ok(*self.levels.next().expect("script exhausted"))
+        }
+
+        fn is_low(&mut self) -> Result<bool, Self::Error> {
+            self.is_high().map(|level| !level)
+        }
+    }
+
+    #[test]
+    fn test_poll() {
+        let levels = [true, false, true, true, true, false, false, false];
+        let mut pin = DebouncedInputPin::new(MockPin::new(&levels), 3, PinState::Low);
+        assert!(pin.is_low());
+
+        assert_eq!(pin.poll(), Ok(None));
+        assert_eq!(pin.poll(), Ok(None));
+        assert_eq!(pin.poll(), Ok(None));
+        assert_eq!(pin.poll(), Ok(None));
+        assert!(!pin.is_high());
+        assert_eq!(
+            pin.poll(),
+            Ok(Some(Edge::new(PinState::Low, PinState::High)))
+        );
+        assert!(pin.is_high());
+
+        assert_eq!(pin.poll(), Ok(None));
+        assert_eq!(pin.poll(), Ok(None));
+        assert_eq!(
+            pin.poll(),
+            Ok(Some(Edge::new(PinState::High, PinState::Low)))
+        );
+        assert!(pin.is_low());
+    }
+
+    #[test]
+    fn test_poll_error() {
+        struct BrokenPin;
+
+        impl ErrorType for BrokenPin {
+            type Error = embedded_hal::digital::ErrorKind;
+        }
+
+        impl InputPin for BrokenPin {
+            fn is_high(&mut self) -> Result<bool, Self::Error> {
+                Err(embedded_hal::digital::ErrorKind::Other)
+            }
+
+            fn is_low(&mut self) -> Result<bool, Self::Error> {
+                Err(embedded_hal::digital::ErrorKind::Other)
+            }
+        }
+
+        let mut pin = DebouncedInputPin::new(BrokenPin, 1, PinState::High);
+        assert_eq!(pin.poll(), Err(embedded_hal::digital::ErrorKind::Other));
+        assert!(pin.is_high());
+    }
+
+    #[test]
+    fn test_hal_pin_state() {
+        use embedded_hal::digital::PinState as HalPinState;
+
+        for &(state, hal_state) in [
+            (PinState::Low, HalPinState::Low),
+            (PinState::High, HalPinState::High),
+        ]
+        .iter()
+        {
+            assert_eq!(PinState::from(hal_state), state);
+            assert_eq!(HalPinState::from(state), hal_state);
+        }
+    }
+
+    #[test]
+    fn test_mirror() {
+        use embedded_hal::digital::OutputPin;
+
+        /// A fake pin recording the last driven level.
+        struct MockOutputPin(Option<bool>);
+
+        impl ErrorType for MockOutputPin {
+            type Error = Infallible;
+        }
+
+        impl OutputPin for MockOutputPin {
+            fn set_low(&mut self) -> Result<(), Self::Error> {
+                self.0 = Some(false);
+                Ok(())
+            }
+
+            fn set_high(&mut self) -> Result<(), Self::Error> {
+                self.0 = Some(true);
+                Ok(())
+            }
+        }
+
+        let mut led = MockOutputPin(None);
+        let mut debouncer = SmallPinDebouncer::new(2, PinState::Low);
+        assert_eq!(debouncer.mirror(&mut led), Ok(()));
+        assert_eq!(led.0, Some(false));
+
+        // Not while settling
+        assert_eq!(debouncer.update(PinState::High), None);
+        assert_eq!(debouncer.mirror(&mut led), Ok(()));
+        assert_eq!(led.0, Some(false));
+
+        assert!(debouncer.update(PinState::High).is_some());
+        assert_eq!(debouncer.mirror(&mut led), Ok(()));
+        assert_eq!(led.0, Some(true));
+
+        assert_eq!(debouncer.update(PinState::Low), None);
+        assert_eq!(debouncer.mirror(&mut led), Ok(()));
+        assert_eq!(led.0, Some(true));
+        assert!(debouncer.update(PinState::Low).is_some());
+        assert_eq!(debouncer.mirror(&mut led), Ok(()));
+        assert_eq!(led.0, Some(false));
+    }
+}
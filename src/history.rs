@@ -0,0 +1,125 @@
+#![deny(unsafe_code)]
+
+use super::debouncer::{Counter, DebounceState, Debouncer, Edge};
+
+/// A debouncer recording its last `N` edges.
+///
+/// Once `N` edges were recorded, every further edge drops the oldest one.
+/// The edges are kept in a ring buffer, so this takes constant time. `N`
+/// has to be at least one, which is checked at compile time.
+#[derive(Debug, Clone)]
+pub struct HistoryDebouncer<T, S, const N: usize> {
+    inner: Debouncer<T, S>,
+    history: heapless::Deque<Edge<T>, N>,
+}
+
+impl<T, S, const N: usize> HistoryDebouncer<T, S, N>
+where
+    T: DebounceState,
+    S: Counter,
+{
+    pub fn new(threshold: S, inital_state: T) -> Self {
+        HistoryDebouncer {
+            inner: Debouncer::new(threshold, inital_state),
+            history: heapless::Deque::new(),
+        }
+    }
+
+    #[must_use = "a detected edge should be handled"]
+    pub fn update(&mut self, state: T) -> Option<Edge<T>> {
+        let edge = self.inner.update(state)?;
+
+        if self.history.is_full() {
+            self.history.pop_front();
+        }
+        // Cannot fail, as there is room for at least one edge
+        let _ = self.history.push_back(edge);
+
+        Some(edge)
+    }
+
+    pub fn is_state(&self, state: T) -> bool {
+        self.inner.is_state(state)
+    }
+
+    /// Returns the recorded edges, oldest first.
+    pub fn history(&self) -> impl Iterator<Item = Edge<T>> + '_ {
+        self.history.iter().copied()
+    }
+
+    pub fn clear_history(&mut self) {
+        self.history.clear();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::pin::PinState::{self, High, Low};
+    use std::vec::Vec;
+
+    fn recorded<const N: usize>(
+        debouncer: &HistoryDebouncer<PinState, u8, N>,
+    ) -> Vec<Edge<PinState>> {
+        debouncer.history().collect()
+    }
+
+    #[test]
+    fn test_history() {
+        let mut debouncer: HistoryDebouncer<PinState, u8, 3> = HistoryDebouncer::new(1, Low);
+        assert!(recorded(&debouncer).is_empty());
+
+        assert_eq!(debouncer.update(High), None);
+        assert_eq!(debouncer.update(High), Some(Edge::new(Low, High)));
+        assert_eq!(recorded(&debouncer), [Edge::new(Low, High)]);
+        assert_eq!(debouncer.update(Low), None);
+        assert_eq!(recorded(&debouncer), [Edge::new(Low, High)]);
+
+        debouncer.clear_history();
+        assert!(recorded(&debouncer).is_empty());
+    }
+
+    #[test]
+    fn test_history_overflow() {
+        let mut debouncer: HistoryDebouncer<PinState, u8, 3> = HistoryDebouncer::new(2, Low);
+
+        // Five edges, alternating
+        for &state in [High, Low, High, Low, High].iter() {
+            assert_eq!(debouncer.update(state), None);
+            assert!(debouncer.update(state).is_some());
+        }
+        assert!(debouncer.is_state(High));
+
+        // Only the last three are kept
+        assert_eq!(
+            recorded(&debouncer),
+            [
+                Edge::new(Low, High),
+                Edge::new(High, Low),
+                Edge::new(Low, High),
+            ]
+        );
+
+        assert!(debouncer.update(Low).is_none());
+        assert!(debouncer.update(Low).is_some());
+        assert_eq!(
+            recorded(&debouncer),
+            [
+                Edge::new(High, Low),
+                Edge::new(Low, High),
+                Edge::new(High, Low),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_single_edge_history() {
+        let mut debouncer: HistoryDebouncer<PinState, u8, 1> = HistoryDebouncer::new(2, Low);
+        assert_eq!(debouncer.update(High), None);
+        assert_eq!(debouncer.update(High), Some(Edge::new(Low, High)));
+        assert_eq!(recorded(&debouncer), [Edge::new(Low, High)]);
+        assert_eq!(debouncer.update(Low), None);
+        assert_eq!(debouncer.update(Low), Some(Edge::new(High, Low)));
+        assert_eq!(recorded(&debouncer), [Edge::new(High, Low)]);
+    }
+}
@@ -0,0 +1,115 @@
+#![deny(unsafe_code)]
+
+use super::debouncer::Edge;
+use super::pin::{PinState, SmallPinDebouncer};
+
+/// Debounces a numeric reading crossing a threshold with hysteresis.
+///
+/// Readings at or above `rising_threshold` count as `High`, readings at or
+/// below `falling_threshold` count as `Low`. Readings in the dead band between
+/// both thresholds count as the level of the previous reading outside of it,
+/// so noise around a single setpoint does not toggle the level. The resulting
+/// levels are then debounced like a pin. `falling_threshold` is expected to
+/// be below `rising_threshold`.
+#[derive(Debug)]
+pub struct HysteresisDebouncer<R> {
+    inner: SmallPinDebouncer,
+    level: PinState,
+    rising_threshold: R,
+    falling_threshold: R,
+}
+
+impl<R> HysteresisDebouncer<R>
+where
+    R: PartialOrd + Copy,
+{
+    pub fn new(
+        rising_threshold: R,
+        falling_threshold: R,
+        threshold: u8,
+        inital_state: PinState,
+    ) -> Self {
+        HysteresisDebouncer {
+            inner: SmallPinDebouncer::new(threshold, inital_state),
+            level: inital_state,
+            rising_threshold,
+            falling_threshold,
+        }
+    }
+
+    #[must_use = "a detected edge should be handled"]
+    pub fn update(&mut self, reading: R) -> Option<Edge<PinState>> {
+        if reading >= self.rising_threshold {
+            self.level = PinState::High;
+        } else if reading <= self.falling_threshold {
+            self.level = PinState::Low;
+        }
+
+        self.inner.update(self.level)
+    }
+
+    pub fn is_high(&self) -> bool {
+        self.inner.is_high()
+    }
+
+    pub fn is_low(&self) -> bool {
+        self.inner.is_low()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_ramp() {
+        let mut debouncer: HysteresisDebouncer<i16> =
+            HysteresisDebouncer::new(30, 20, 2, PinState::Low);
+        assert!(debouncer.is_low());
+
+        // Ramp up through the dead band
+        assert_eq!(debouncer.update(10), None);
+        assert_eq!(debouncer.update(20), None);
+        assert_eq!(debouncer.update(25), None);
+        assert_eq!(debouncer.update(29), None);
+        assert!(debouncer.is_low());
+        assert_eq!(debouncer.update(30), None);
+        assert_eq!(
+            debouncer.update(35),
+            Some(Edge::new(PinState::Low, PinState::High))
+        );
+
+        // Noise within the dead band keeps the level
+        assert_eq!(debouncer.update(22), None);
+        assert_eq!(debouncer.update(28), None);
+        assert_eq!(debouncer.update(21), None);
+        assert!(debouncer.is_high());
+
+        // Ramp down
+        assert_eq!(debouncer.update(20), None);
+        assert_eq!(
+            debouncer.update(15),
+            Some(Edge::new(PinState::High, PinState::Low))
+        );
+        assert_eq!(debouncer.update(29), None);
+        assert_eq!(debouncer.update(25), None);
+        assert!(debouncer.is_low());
+    }
+
+    #[test]
+    fn test_spike() {
+        let mut debouncer: HysteresisDebouncer<f32> =
+            HysteresisDebouncer::new(1.5, 0.5, 3, PinState::Low);
+
+        // A single spike above the band is debounced
+        assert_eq!(debouncer.update(2.0), None);
+        assert_eq!(debouncer.update(0.2), None);
+        assert_eq!(debouncer.update(1.0), None);
+        assert_eq!(debouncer.update(2.0), None);
+        assert_eq!(debouncer.update(1.0), None);
+        assert_eq!(
+            debouncer.update(1.2),
+            Some(Edge::new(PinState::Low, PinState::High))
+        );
+    }
+}
@@ -0,0 +1,154 @@
+#![deny(unsafe_code)]
+
+use super::debouncer::Edge;
+
+/// A debouncer integrating the samples, like an RC filter.
+///
+/// Every sample of the candidate state increments the integral, every other
+/// sample decrements it again, floored at zero. The candidate is committed
+/// once the integral reaches `threshold`, and it is dropped once the integral
+/// falls back to zero.
+///
+/// Unlike `Debouncer`, where any other sample restarts the count, a brief
+/// reversion only costs one sample, so an input with symmetric noise still
+/// commits eventually, just later than a clean one. Like with `Debouncer`,
+/// the first differing sample never commits, so thresholds of 0 and 1 behave
+/// like 2.
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub struct IntegratingDebouncer<T, S> {
+    current_state: T,
+    next_state: T,
+    // Zero exactly when no transition is in progress
+    integral: S,
+    threshold: S,
+}
+
+impl<T, S> IntegratingDebouncer<T, S>
+where
+    T: PartialEq + Copy,
+    S: num::traits::Zero + num::traits::One + core::ops::Sub<Output = S> + PartialOrd + Copy,
+{
+    pub fn new(threshold: S, inital_state: T) -> Self {
+        IntegratingDebouncer {
+            current_state: inital_state,
+            next_state: inital_state,
+            integral: S::zero(),
+            threshold,
+        }
+    }
+
+    #[must_use = "a detected edge should be handled"]
+    pub fn update(&mut self, state: T) -> Option<Edge<T>> {
+        if self.integral == S::zero() {
+            self.next_state = state;
+        }
+
+        if self.next_state == self.current_state {
+            None
+        } else if self.next_state == state {
+            self.integral = self.integral + S::one();
+
+            // The first sample never commits, even for thresholds below two
+            if self.integral >= self.threshold && self.integral > S::one() {
+                let from_state = self.current_state;
+
+                self.current_state = state;
+                self.integral = S::zero();
+
+                Some(Edge::new(from_state, state))
+            } else {
+                None
+            }
+        } else {
+            self.integral = self.integral - S::one();
+            if self.integral == S::zero() {
+                self.next_state = self.current_state;
+            }
+
+            None
+        }
+    }
+
+    pub fn is_state(&self, state: T) -> bool {
+        self.current_state == self.next_state && self.current_state == state
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::debouncer::Debouncer;
+    use crate::pin::PinState::{self, High, Low};
+
+    #[test]
+    fn test_clean_transition() {
+        let mut debouncer: IntegratingDebouncer<PinState, u8> = IntegratingDebouncer::new(3, Low);
+        assert!(debouncer.is_state(Low));
+        assert_eq!(debouncer.update(Low), None);
+        assert_eq!(debouncer.update(High), None);
+        assert_eq!(debouncer.update(High), None);
+        assert!(!debouncer.is_state(High));
+        assert_eq!(debouncer.update(High), Some(Edge::new(Low, High)));
+        assert!(debouncer.is_state(High));
+    }
+
+    /// Noise slows down the commit, but does not prevent it.
+    #[test]
+    fn test_noisy_transition() {
+        let samples = [High, High, Low, High, Low, High, High];
+
+        let mut integrating: IntegratingDebouncer<PinState, u8> = IntegratingDebouncer::new(3, Low);
+        let mut default: Debouncer<PinState, u8> = Debouncer::new(3, Low);
+        for &state in samples[..6].iter() {
+            assert_eq!(integrating.update(state), None);
+            assert_eq!(default.update(state), None);
+        }
+        assert_eq!(integrating.update(samples[6]), Some(Edge::new(Low, High)));
+        assert_eq!(default.update(samples[6]), None);
+    }
+
+    /// Alternating noise never reaches the threshold.
+    #[test]
+    fn test_alternating_noise() {
+        let mut debouncer: IntegratingDebouncer<PinState, u8> = IntegratingDebouncer::new(2, Low);
+        for _ in 0..10 {
+            assert_eq!(debouncer.update(High), None);
+            assert_eq!(debouncer.update(Low), None);
+            assert!(debouncer.is_state(Low));
+        }
+    }
+
+    /// Other candidates only decrement the integral.
+    #[test]
+    fn test_three_states() {
+        let mut debouncer: IntegratingDebouncer<char, u8> = IntegratingDebouncer::new(3, 'a');
+        assert_eq!(debouncer.update('b'), None);
+        assert_eq!(debouncer.update('b'), None);
+        assert_eq!(debouncer.update('c'), None);
+        assert_eq!(debouncer.update('c'), None);
+        assert!(debouncer.is_state('a'));
+
+        // The integral is empty, so `c` becomes the candidate
+        assert_eq!(debouncer.update('c'), None);
+        assert_eq!(debouncer.update('c'), None);
+        assert_eq!(debouncer.update('c'), Some(Edge::new('a', 'c')));
+    }
+
+    /// Thresholds below two behave like two, as for `Debouncer`.
+    #[test]
+    fn test_threshold_one() {
+        for threshold in 1..=2 {
+            let mut integrating: IntegratingDebouncer<PinState, u8> =
+                IntegratingDebouncer::new(threshold, Low);
+            let mut default: Debouncer<PinState, u8> = Debouncer::new(threshold, Low);
+            for &state in [High, Low, High, High, High].iter() {
+                assert_eq!(integrating.update(state), default.update(state));
+            }
+            assert!(integrating.is_state(High));
+        }
+
+        let mut debouncer: IntegratingDebouncer<PinState, u8> = IntegratingDebouncer::new(0, Low);
+        assert_eq!(debouncer.update(High), None);
+        assert_eq!(debouncer.update(High), Some(Edge::new(Low, High)));
+    }
+}
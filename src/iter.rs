@@ -0,0 +1,64 @@
+#![deny(unsafe_code)]
+
+use super::debouncer::Edge;
+use super::pin::{PinState, SmallPinDebouncer};
+
+/// An iterator yielding the debounced edges of an iterator of samples.
+#[derive(Debug)]
+pub struct DebounceIter<I> {
+    iter: I,
+    debouncer: SmallPinDebouncer,
+}
+
+impl<I> Iterator for DebounceIter<I>
+where
+    I: Iterator<Item = PinState>,
+{
+    type Item = Edge<PinState>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let debouncer = &mut self.debouncer;
+        self.iter.find_map(|state| debouncer.update(state))
+    }
+}
+
+/// Adds `debounce` to every iterator of `PinState` samples.
+pub trait DebounceExt: Iterator<Item = PinState> + Sized {
+    fn debounce(self, threshold: u8, inital_state: PinState) -> DebounceIter<Self> {
+        DebounceIter {
+            iter: self,
+            debouncer: SmallPinDebouncer::new(threshold, inital_state),
+        }
+    }
+}
+
+impl<I> DebounceExt for I where I: Iterator<Item = PinState> {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::vec::Vec;
+
+    use PinState::{High, Low};
+
+    #[test]
+    fn test_debounce() {
+        let samples = [
+            Low, High, Low, High, High, High, Low, High, High, Low, Low, Low, Low, High,
+        ];
+
+        let edges: Vec<_> = samples.iter().copied().debounce(3, Low).collect();
+        assert_eq!(edges, [Edge::new(Low, High), Edge::new(High, Low)]);
+
+        // No run of highs is long enough
+        let edges: Vec<_> = samples.iter().copied().debounce(4, Low).collect();
+        assert!(edges.is_empty());
+    }
+
+    #[test]
+    fn test_debounce_without_edges() {
+        let samples = [High, High, Low, High];
+        assert_eq!(samples.iter().copied().debounce(3, High).next(), None);
+        assert_eq!(core::iter::empty().debounce(3, High).next(), None);
+    }
+}
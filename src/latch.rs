@@ -0,0 +1,81 @@
+#![deny(unsafe_code)]
+
+use super::debouncer::{Counter, DebounceState, Debouncer, Edge};
+
+/// A debouncer remembering the last edge until it is taken.
+///
+/// This allows sampling e.g. in an interrupt handler while the edges are
+/// handled later in the main loop. Only the newest edge is kept.
+#[derive(Debug)]
+pub struct LatchingDebouncer<T, S> {
+    inner: Debouncer<T, S>,
+    latched: Option<Edge<T>>,
+}
+
+impl<T, S> LatchingDebouncer<T, S>
+where
+    T: DebounceState,
+    S: Counter,
+{
+    pub fn new(threshold: S, inital_state: T) -> Self {
+        LatchingDebouncer {
+            inner: Debouncer::new(threshold, inital_state),
+            latched: None,
+        }
+    }
+
+    /// Feeds a sample and latches a detected edge, replacing any older one.
+    ///
+    /// The edge is returned as well, but may also be ignored in favour of
+    /// `take_edge`.
+    pub fn update(&mut self, state: T) -> Option<Edge<T>> {
+        let edge = self.inner.update(state);
+        if edge.is_some() {
+            self.latched = edge;
+        }
+
+        edge
+    }
+
+    /// Returns the latched edge and clears the latch.
+    pub fn take_edge(&mut self) -> Option<Edge<T>> {
+        self.latched.take()
+    }
+
+    pub fn is_state(&self, state: T) -> bool {
+        self.inner.is_state(state)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::pin::PinState::{self, High, Low};
+
+    #[test]
+    fn test_latch_until_taken() {
+        let mut debouncer: LatchingDebouncer<PinState, u8> = LatchingDebouncer::new(2, Low);
+        assert_eq!(debouncer.take_edge(), None);
+
+        debouncer.update(High);
+        debouncer.update(High);
+        for _ in 0..5 {
+            debouncer.update(High);
+        }
+        assert!(debouncer.is_state(High));
+
+        assert_eq!(debouncer.take_edge(), Some(Edge::new(Low, High)));
+        assert_eq!(debouncer.take_edge(), None);
+    }
+
+    #[test]
+    fn test_latch_overwritten() {
+        let mut debouncer: LatchingDebouncer<PinState, u8> = LatchingDebouncer::new(2, Low);
+        for &state in [High, High, Low, Low, Low].iter() {
+            debouncer.update(state);
+        }
+
+        assert_eq!(debouncer.take_edge(), Some(Edge::new(High, Low)));
+        assert_eq!(debouncer.take_edge(), None);
+    }
+}
@@ -1,5 +1,63 @@
-#![cfg_attr(not(test), no_std)]
-#![deny(unsafe_code)]
+//! Generic state debouncer for complete graphs.
+//!
+//! The crate does not contain any `unsafe` code. This is part of its API
+//! promise and enforced by `#![forbid(unsafe_code)]`.
 
+#![no_std]
+#![forbid(unsafe_code)]
+
+#[cfg(feature = "alloc")]
+extern crate alloc;
+#[cfg(test)]
+extern crate std;
+
+/// Compiles the items only without the optional counters, which add to the
+/// size of a debouncer on purpose. Used to check the promised RAM
+/// consumption.
+macro_rules! cfg_no_optional_counters {
+    ($($item:item)*) => {
+        $(
+            #[cfg(not(any(
+                feature = "edge-count",
+                feature = "stable-count",
+                feature = "glitch-count",
+                feature = "edge-latency",
+                feature = "last-edge",
+                feature = "settled-samples"
+            )))]
+            $item
+        )*
+    };
+}
+
+pub mod adc;
+pub mod bank;
+pub mod button;
+pub mod callback;
+pub mod clone;
+pub mod comparator;
 pub mod debouncer;
-pub mod pin;
\ No newline at end of file
+pub mod fixed;
+pub mod hysteresis;
+pub mod integrator;
+pub mod iter;
+pub mod latch;
+pub mod majority;
+pub mod pattern;
+pub mod pin;
+pub mod quadrature;
+pub mod table;
+pub mod timed;
+pub mod tristate;
+
+#[cfg(feature = "embedded-hal")]
+pub mod hal;
+
+#[cfg(feature = "async")]
+pub mod stream;
+
+#[cfg(feature = "heapless")]
+pub mod history;
+
+#[cfg(feature = "gpio-cdev")]
+pub mod cdev;
@@ -0,0 +1,121 @@
+#![deny(unsafe_code)]
+
+use super::debouncer::Edge;
+use super::pin::PinState;
+
+/// Debounces a pin by a majority vote over the last `W` samples.
+///
+/// The window starts filled with the initial state. A level is committed once
+/// a strict majority, i.e. more than `W / 2`, of the samples in the window
+/// differ from the committed level. For an even `W`, a tie keeps the
+/// committed level. Unlike `SmallPinDebouncer`, the samples do not need to be
+/// consecutive, so intermittent noise only delays an edge. The window is
+/// stored as bits, so `W` has to be in `1..=32`.
+#[derive(Debug, Clone, Copy)]
+pub struct MajorityDebouncer<const W: usize> {
+    // The newest sample in the lowest bit, set for `High`
+    history: u32,
+    level: PinState,
+}
+
+impl<const W: usize> MajorityDebouncer<W> {
+    const MASK: u32 = if W >= 32 { u32::MAX } else { (1 << W) - 1 };
+    // Evaluated when referenced, failing the build for an invalid window
+    const VALID_WINDOW: () = assert!(W >= 1 && W <= 32, "the window must hold 1 to 32 samples");
+
+    pub fn new(inital_state: PinState) -> Self {
+        let () = Self::VALID_WINDOW;
+
+        MajorityDebouncer {
+            history: match inital_state {
+                PinState::Low => 0,
+                PinState::High => Self::MASK,
+            },
+            level: inital_state,
+        }
+    }
+
+    #[must_use = "a detected edge should be handled"]
+    pub fn update(&mut self, state: PinState) -> Option<Edge<PinState>> {
+        self.history = (self.history << 1 | (state == PinState::High) as u32) & Self::MASK;
+
+        let highs = self.history.count_ones() as usize;
+        let level = if highs * 2 > W {
+            PinState::High
+        } else if (W - highs) * 2 > W {
+            PinState::Low
+        } else {
+            self.level
+        };
+
+        if level == self.level {
+            None
+        } else {
+            let from_state = self.level;
+            self.level = level;
+
+            Some(Edge::new(from_state, level))
+        }
+    }
+
+    pub fn is_high(&self) -> bool {
+        self.level == PinState::High
+    }
+
+    pub fn is_low(&self) -> bool {
+        self.level == PinState::Low
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use PinState::{High, Low};
+
+    #[test]
+    fn test_three_of_five() {
+        let mut debouncer: MajorityDebouncer<5> = MajorityDebouncer::new(Low);
+        assert!(debouncer.is_low());
+
+        // Intermittent noise does not restart the vote
+        assert_eq!(debouncer.update(High), None);
+        assert_eq!(debouncer.update(Low), None);
+        assert_eq!(debouncer.update(High), None);
+        assert_eq!(debouncer.update(Low), None);
+        assert_eq!(debouncer.update(High), Some(Edge::new(Low, High)));
+        assert!(debouncer.is_high());
+
+        // The window never holds more than two lows
+        assert_eq!(debouncer.update(High), None);
+        assert_eq!(debouncer.update(Low), None);
+        assert_eq!(debouncer.update(High), None);
+        assert_eq!(debouncer.update(Low), None);
+        assert!(debouncer.is_high());
+        assert_eq!(debouncer.update(Low), Some(Edge::new(High, Low)));
+        assert!(debouncer.is_low());
+    }
+
+    #[test]
+    fn test_tie_keeps_level() {
+        let mut debouncer: MajorityDebouncer<4> = MajorityDebouncer::new(High);
+        assert_eq!(debouncer.update(Low), None);
+        assert_eq!(debouncer.update(Low), None);
+        assert!(debouncer.is_high());
+        assert_eq!(debouncer.update(Low), Some(Edge::new(High, Low)));
+
+        for _ in 0..10 {
+            assert_eq!(debouncer.update(High), None);
+            assert_eq!(debouncer.update(Low), None);
+        }
+        assert!(debouncer.is_low());
+    }
+
+    #[test]
+    fn test_full_width() {
+        let mut debouncer: MajorityDebouncer<32> = MajorityDebouncer::new(High);
+        for _ in 0..16 {
+            assert_eq!(debouncer.update(Low), None);
+        }
+        assert_eq!(debouncer.update(Low), Some(Edge::new(High, Low)));
+    }
+}
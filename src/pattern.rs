@@ -0,0 +1,82 @@
+#![deny(unsafe_code)]
+
+use super::pin::PinState;
+
+/// An endless, reproducible sequence of bouncing pin samples.
+///
+/// Before every sample, the level flips with a probability of
+/// `flip_chance / 256`, so low values yield long stable runs and high values
+/// a heavily bouncing line. The randomness comes from a xorshift generator,
+/// so the same seed always yields the same sequence.
+#[derive(Debug, Clone)]
+pub struct BouncePattern {
+    random: u32,
+    flip_chance: u8,
+    level: PinState,
+}
+
+impl BouncePattern {
+    pub fn new(seed: u32, flip_chance: u8, inital_state: PinState) -> Self {
+        BouncePattern {
+            // Xorshift would only ever yield zero for a zero seed
+            random: if seed == 0 { 0x9e37_79b9 } else { seed },
+            flip_chance,
+            level: inital_state,
+        }
+    }
+}
+
+impl Iterator for BouncePattern {
+    type Item = PinState;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.random ^= self.random << 13;
+        self.random ^= self.random >> 17;
+        self.random ^= self.random << 5;
+
+        if ((self.random >> 24) as u8) < self.flip_chance {
+            self.level = !self.level;
+        }
+
+        Some(self.level)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::vec::Vec;
+
+    #[test]
+    fn test_reproducible() {
+        let first: Vec<_> = BouncePattern::new(42, 64, PinState::Low)
+            .take(100)
+            .collect();
+        let second: Vec<_> = BouncePattern::new(42, 64, PinState::Low)
+            .take(100)
+            .collect();
+        assert_eq!(first, second);
+
+        let other: Vec<_> = BouncePattern::new(43, 64, PinState::Low)
+            .take(100)
+            .collect();
+        assert_ne!(first, other);
+    }
+
+    #[test]
+    fn test_flip_chance() {
+        let flips = |flip_chance| {
+            let samples: Vec<_> = BouncePattern::new(7, flip_chance, PinState::High)
+                .take(1000)
+                .collect();
+            samples.windows(2).filter(|pair| pair[0] != pair[1]).count()
+        };
+
+        assert_eq!(flips(0), 0);
+        assert!(flips(16) < flips(128));
+
+        // A zero seed still bounces
+        let mut pattern = BouncePattern::new(0, 128, PinState::Low);
+        assert!(pattern.any(|state| state == PinState::High));
+    }
+}
@@ -1,41 +1,1276 @@
 #![deny(unsafe_code)]
 
-use super::debouncer::{Debouncer, Edge};
+use core::convert::TryFrom;
 
-#[derive(Debug, PartialEq, Clone, Copy)]
+use super::debouncer::{effective_threshold, Debouncer, Edge, Ordinal, TwoState};
+
+/// The level of a pin, ordered `Low < High`.
+#[derive(Debug, PartialEq, Eq, PartialOrd, Ord, Clone, Copy)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum PinState {
     Low,
     High,
 }
 
-#[derive(Debug)]
+impl PinState {
+    /// Swaps `High` and `Low` in place.
+    pub fn toggle(&mut self) {
+        *self = !*self;
+    }
+
+    /// Returns the opposite level.
+    pub fn toggled(self) -> PinState {
+        !self
+    }
+
+    /// Converts a raw read of active low wiring, where a pressed button
+    /// pulls the line low.
+    ///
+    /// A low line (`false`) therefore means pressed and becomes `High`, and
+    /// a high line (`true`) becomes `Low`.
+    pub fn from_active_low(raw: bool) -> PinState {
+        if raw {
+            PinState::Low
+        } else {
+            PinState::High
+        }
+    }
+}
+
+impl TwoState for PinState {
+    fn is_high(&self) -> bool {
+        *self == PinState::High
+    }
+}
+
+/// `Low` is 0 and `High` is 1, so a rising edge has a delta of +1.
+impl Ordinal for PinState {
+    fn ordinal(&self) -> i32 {
+        match self {
+            PinState::Low => 0,
+            PinState::High => 1,
+        }
+    }
+}
+
+impl core::ops::Not for PinState {
+    type Output = PinState;
+
+    fn not(self) -> Self::Output {
+        match self {
+            PinState::Low => PinState::High,
+            PinState::High => PinState::Low,
+        }
+    }
+}
+
+impl Edge<PinState> {
+    /// Packs the edge into a single byte, e.g. to log it over a slow link.
+    ///
+    /// Bit 0 holds the `from` level and bit 1 the `to` level, each set for
+    /// `High`. A rising edge is therefore `0b10` and a falling edge `0b01`.
+    /// All other bits are zero.
+    pub fn to_code(&self) -> u8 {
+        let (from, to) = self.into_parts();
+        (from == PinState::High) as u8 | ((to == PinState::High) as u8) << 1
+    }
+
+    /// Unpacks an edge packed by `to_code`.
+    ///
+    /// Returns `None` if any bit besides bit 0 and 1 is set.
+    pub fn from_code(code: u8) -> Option<Edge<PinState>> {
+        if code > 0b11 {
+            return None;
+        }
+
+        let level = |bit: u8| {
+            if code & bit == 0 {
+                PinState::Low
+            } else {
+                PinState::High
+            }
+        };
+
+        Some(Edge::new(level(0b01), level(0b10)))
+    }
+}
+
+/// Converts an edge into whether it is rising, i.e. `Low -> High`.
+///
+/// A `High -> Low` edge yields `false`.
+impl From<Edge<PinState>> for bool {
+    fn from(edge: Edge<PinState>) -> bool {
+        edge.into_parts() == (PinState::Low, PinState::High)
+    }
+}
+
+/// Computes the threshold to debounce for `debounce_ms` when sampling every
+/// `sample_period_ms`.
+///
+/// The number of samples is rounded up, so the debounce time is never
+/// shorter than requested. The threshold is at least 1 and saturates at
+/// `u8::MAX`, which is also returned for a sample period of zero.
+pub fn threshold_for_duration(debounce_ms: u32, sample_period_ms: u32) -> u8 {
+    if sample_period_ms == 0 {
+        return u8::MAX;
+    }
+
+    let mut samples = debounce_ms / sample_period_ms;
+    if debounce_ms % sample_period_ms != 0 {
+        samples += 1;
+    }
+
+    if samples < 1 {
+        1
+    } else if samples > u32::from(u8::MAX) {
+        u8::MAX
+    } else {
+        samples as u8
+    }
+}
+
+/// Counts the edges of `samples` for every threshold in `thresholds`.
+///
+/// This helps to find the smallest threshold filtering out the bouncing of a
+/// recorded input, i.e. from which on the edge count stops dropping.
+#[cfg(feature = "alloc")]
+pub fn sweep_thresholds(
+    samples: &[PinState],
+    thresholds: core::ops::RangeInclusive<u8>,
+    initial: PinState,
+) -> alloc::vec::Vec<(u8, usize)> {
+    thresholds
+        .map(|threshold| {
+            let mut debouncer = SmallPinDebouncer::new(threshold, initial);
+            let edges = samples
+                .iter()
+                .filter(|&&state| debouncer.update(state).is_some())
+                .count();
+
+            (threshold, edges)
+        })
+        .collect()
+}
+
+/// The error of converting a byte other than `0` or `1` into a `PinState`.
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub struct InvalidPinState(pub u8);
+
+/// Converts `0` to `Low` and `1` to `High`.
+impl TryFrom<u8> for PinState {
+    type Error = InvalidPinState;
+
+    fn try_from(value: u8) -> Result<Self, Self::Error> {
+        match value {
+            0 => Ok(PinState::Low),
+            1 => Ok(PinState::High),
+            _ => Err(InvalidPinState(value)),
+        }
+    }
+}
+
+impl Debouncer<PinState, u8> {
+    /// Encodes the debouncer into four bytes, e.g. to persist it in flash
+    /// without `serde`.
+    ///
+    /// The bytes are the committed state, the pending state, the samples of
+    /// the pending state counted so far and the threshold. States are encoded
+    /// as `0` for `Low` and `1` for `High`. Optional counters like the edge
+    /// count are not stored.
+    pub fn to_bytes(&self) -> [u8; 4] {
+        let snapshot = self.snapshot();
+        [
+            snapshot.current_state as u8,
+            snapshot.next_state as u8,
+            snapshot.repetition_count,
+            snapshot.threshold,
+        ]
+    }
+
+    /// Decodes a debouncer encoded by `to_bytes`, with all optional counters
+    /// zeroed.
+    ///
+    /// Returns `None` for bytes `to_bytes` never returns, like a deserialized
+    /// debouncer: if a state is invalid, the threshold is zero, the count is
+    /// zero while the states differ or nonzero while they are equal, or the
+    /// count would already have committed the pending state.
+    pub fn from_bytes(bytes: [u8; 4]) -> Option<Self> {
+        let [current_state, next_state, count, threshold] = bytes;
+        let current_state = PinState::try_from(current_state).ok()?;
+        let next_state = PinState::try_from(next_state).ok()?;
+        if (current_state != next_state) != (count > 0) || count >= effective_threshold(threshold) {
+            return None;
+        }
+
+        Debouncer::with_initial_count(threshold, current_state, next_state, count)
+    }
+}
+
+/// A generic pin debouncer for thresholds up to `u8::MAX`.
+pub type TinyPinDebouncer = Debouncer<PinState, u8>;
+
+/// A generic pin debouncer for thresholds up to `u16::MAX`.
+pub type WidePinDebouncer = Debouncer<PinState, u16>;
+
+#[derive(Debug, Clone, Copy)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct SmallPinDebouncer {
     inner: Debouncer<PinState, u8>,
+    /// Flipped by `poll_toggle` on every rising edge.
+    latch: bool,
+}
+
+// Fails the build if a change bloats the pin debouncers, which is checked at
+// runtime by `test_ram_consumption` as well.
+cfg_no_optional_counters! {
+    const _: () = {
+        assert!(
+            core::mem::size_of::<Debouncer<PinState, u8>>() == 4,
+            "Debouncer<PinState, u8> must take exactly 4 bytes"
+        );
+        assert!(
+            core::mem::size_of::<SmallPinDebouncer>() == 5,
+            "SmallPinDebouncer must take exactly 5 bytes"
+        );
+    };
 }
 
 impl SmallPinDebouncer {
     pub fn new(threshold: u8, inital_state: PinState) -> Self {
         SmallPinDebouncer {
             inner: Debouncer::new(threshold, inital_state),
+            latch: false,
+        }
+    }
+
+    /// Creates a debouncer which already counted half of the threshold
+    /// towards `expected_first`, to reduce the latency of the first edge.
+    ///
+    /// If the first samples are `expected_first`, the first edge is reported
+    /// after `threshold - threshold / 2` samples instead of `threshold`. The
+    /// tradeoff is that the debouncer is neither high nor low until then, and
+    /// that the head start is lost with the first sample of `inital_state`.
+    /// If `expected_first` equals `inital_state`, or the threshold is below
+    /// two and thus has no half to count, this is the same as `new`.
+    pub fn new_primed_toward(
+        threshold: u8,
+        inital_state: PinState,
+        expected_first: PinState,
+    ) -> Self {
+        // The count never exceeds the threshold
+        let inner =
+            Debouncer::with_initial_count(threshold, inital_state, expected_first, threshold / 2)
+                .unwrap_or_else(|| Debouncer::new(threshold, inital_state));
+
+        SmallPinDebouncer {
+            inner,
+            latch: false,
         }
     }
 
+    /// Creates a debouncer warmed up with a prelude of known good samples.
+    ///
+    /// The debouncer starts in the first sample, or `Low` for an empty
+    /// prelude, and the remaining samples are fed with their edges discarded.
+    /// The resulting state therefore reflects the prelude, including a
+    /// transition still in progress at its end.
+    pub fn from_prelude(threshold: u8, samples: &[PinState]) -> Self {
+        let inital_state = samples.first().copied().unwrap_or(PinState::Low);
+        let mut debouncer = SmallPinDebouncer::new(threshold, inital_state);
+        debouncer.inner.update_batch_count(samples);
+
+        debouncer
+    }
+
+    /// Returns the committed state after debouncing recorded raw reads, with
+    /// `true` meaning `High`.
+    ///
+    /// A transition still in progress after the last read is not committed.
+    pub fn final_state(threshold: u8, inital_state: PinState, samples: &[bool]) -> PinState {
+        let mut debouncer = Debouncer::new(threshold, inital_state);
+        for &sample in samples {
+            let state = if sample {
+                PinState::High
+            } else {
+                PinState::Low
+            };
+            let _ = debouncer.update(state);
+        }
+
+        debouncer.snapshot().current_state
+    }
+
+    /// Like `Debouncer::replay`, feeds all samples into a new debouncer and
+    /// returns it along with the edges encountered.
+    #[cfg(feature = "alloc")]
+    pub fn replay(
+        threshold: u8,
+        inital_state: PinState,
+        samples: impl IntoIterator<Item = PinState>,
+    ) -> (Self, alloc::vec::Vec<Edge<PinState>>) {
+        let (inner, edges) = Debouncer::replay(threshold, inital_state, samples);
+
+        let debouncer = SmallPinDebouncer {
+            inner,
+            latch: false,
+        };
+
+        (debouncer, edges)
+    }
+
+    /// Like `new`, but usable in `const` and `static` items.
+    pub const fn new_const(threshold: u8, inital_state: PinState) -> Self {
+        SmallPinDebouncer {
+            inner: Debouncer::new_const(threshold, inital_state),
+            latch: false,
+        }
+    }
+
+    #[must_use = "a detected edge should be handled"]
+    #[inline]
     pub fn update(&mut self, state: PinState) -> Option<Edge<PinState>> {
         self.inner.update(state)
     }
 
+    /// Feeds a sample read from a fallible source.
+    ///
+    /// An error is returned as is. It neither advances nor resets the count,
+    /// as if the sample was never taken.
+    #[must_use = "a detected edge should be handled"]
+    pub fn update_result<E>(
+        &mut self,
+        state: Result<PinState, E>,
+    ) -> Result<Option<Edge<PinState>>, E> {
+        state.map(|state| self.update(state))
+    }
+
+    /// Lazily feeds `samples` while the returned iterator is advanced and
+    /// yields the detected edges.
+    ///
+    /// Samples not pulled from the iterator are not fed.
+    pub fn edges<'a, I>(&'a mut self, samples: I) -> impl Iterator<Item = Edge<PinState>> + 'a
+    where
+        I: IntoIterator<Item = PinState>,
+        I::IntoIter: 'a,
+    {
+        samples
+            .into_iter()
+            .filter_map(move |state| self.update(state))
+    }
+
+    #[inline]
     pub fn is_high(&self) -> bool {
         self.inner.is_state(PinState::High)
     }
 
+    #[inline]
     pub fn is_low(&self) -> bool {
         self.inner.is_state(PinState::Low)
     }
+
+    /// Returns whether the next committed edge is a rising one.
+    ///
+    /// A rising edge disarms the debouncer, and only the falling edge
+    /// committing `Low` again re-arms it. Bounces back to `Low` shorter than
+    /// the threshold do not re-arm, so a bouncing switch fires a single rising
+    /// edge per press. The flag is derived from the committed state instead
+    /// of being stored.
+    pub fn is_armed(&self) -> bool {
+        self.inner.snapshot().current_state == PinState::Low
+    }
+
+    /// Feeds a sample and calls `on_rising` or `on_falling` if an edge was
+    /// detected.
+    pub fn poll_with(
+        &mut self,
+        state: PinState,
+        mut on_rising: impl FnMut(),
+        mut on_falling: impl FnMut(),
+    ) {
+        match self.update(state) {
+            Some(edge) if edge == Edge::new(PinState::Low, PinState::High) => on_rising(),
+            Some(_) => on_falling(),
+            None => {}
+        }
+    }
+
+    /// Feeds a sample, flips the stored latch on a debounced `Low -> High`
+    /// edge and returns it, e.g. for click to toggle fixtures.
+    ///
+    /// The latch starts cleared and is left unchanged by falling edges.
+    pub fn poll_toggle(&mut self, state: PinState) -> bool {
+        if let Some(edge) = self.update(state) {
+            if edge == Edge::new(PinState::Low, PinState::High) {
+                self.latch = !self.latch;
+            }
+        }
+
+        self.latch
+    }
+
+    /// Drives `out` to the committed level, e.g. for an LED mirroring a
+    /// debounced input.
+    ///
+    /// While a transition is in progress, the previous level is kept.
+    #[cfg(feature = "embedded-hal")]
+    pub fn mirror<O>(&self, out: &mut O) -> Result<(), O::Error>
+    where
+        O: embedded_hal::digital::OutputPin,
+    {
+        out.set_state(self.inner.snapshot().current_state.into())
+    }
+
+    pub fn builder() -> SmallPinDebouncerBuilder {
+        SmallPinDebouncerBuilder::default()
+    }
+}
+
+/// Debounces with a threshold of 4 and starts `Low`.
+impl Default for SmallPinDebouncer {
+    fn default() -> Self {
+        SmallPinDebouncer::builder().build()
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+pub struct SmallPinDebouncerBuilder {
+    threshold: u8,
+    inital_state: PinState,
+}
+
+impl SmallPinDebouncerBuilder {
+    pub fn threshold(mut self, threshold: u8) -> Self {
+        self.threshold = threshold;
+        self
+    }
+
+    pub fn initial_state(mut self, inital_state: PinState) -> Self {
+        self.inital_state = inital_state;
+        self
+    }
+
+    pub fn build(self) -> SmallPinDebouncer {
+        SmallPinDebouncer::new(self.threshold, self.inital_state)
+    }
+}
+
+impl Default for SmallPinDebouncerBuilder {
+    fn default() -> Self {
+        SmallPinDebouncerBuilder {
+            threshold: 4,
+            inital_state: PinState::Low,
+        }
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::debouncer::DebouncerError;
+
+    /// Ensure the `defmt` derives resolve for pin edges.
+    #[cfg(feature = "defmt")]
+    #[test]
+    fn test_defmt_format() {
+        fn assert_format<F: defmt::Format>() {}
+
+        assert_format::<PinState>();
+        assert_format::<Edge<PinState>>();
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn test_serde_round_trip() {
+        for state in [PinState::Low, PinState::High].iter() {
+            let json = serde_json::to_string(state).unwrap();
+            assert_eq!(serde_json::from_str::<PinState>(&json).unwrap(), *state);
+        }
+
+        let mut debouncer = SmallPinDebouncer::new(2, PinState::High);
+        assert_eq!(debouncer.update(PinState::Low), None);
+
+        let json = serde_json::to_string(&debouncer).unwrap();
+        let mut restored: SmallPinDebouncer = serde_json::from_str(&json).unwrap();
+        assert!(!restored.is_high());
+        assert_eq!(
+            restored.update(PinState::Low),
+            Some(Edge::new(PinState::High, PinState::Low))
+        );
+    }
+
+    #[test]
+    fn test_is_armed() {
+        use PinState::{High, Low};
+
+        let mut debouncer = SmallPinDebouncer::new(3, Low);
+        assert!(debouncer.is_armed());
+        assert_eq!(
+            debouncer.edges([High, High, High].iter().copied()).count(),
+            1
+        );
+        assert!(!debouncer.is_armed());
+
+        // Brief returns to low neither re-arm nor fire a second rising edge
+        let bounces = [Low, High, Low, Low, High, High, High];
+        assert_eq!(debouncer.edges(bounces.iter().copied()).count(), 0);
+        assert!(!debouncer.is_armed());
+
+        // Settling towards low is not enough either
+        assert_eq!(debouncer.update(Low), None);
+        assert_eq!(debouncer.update(Low), None);
+        assert!(!debouncer.is_armed());
+        assert_eq!(debouncer.update(Low), Some(Edge::new(High, Low)));
+        assert!(debouncer.is_armed());
+
+        assert_eq!(
+            debouncer.edges([High, High, High].iter().copied()).last(),
+            Some(Edge::new(Low, High))
+        );
+    }
+
+    #[test]
+    fn test_copy() {
+        use PinState::{High, Low};
+
+        #[derive(Clone, Copy)]
+        struct Inputs {
+            start: SmallPinDebouncer,
+            stop: SmallPinDebouncer,
+        }
+
+        let mut inputs = Inputs {
+            start: SmallPinDebouncer::new(2, Low),
+            stop: SmallPinDebouncer::new(2, Low),
+        };
+        assert_eq!(inputs.start.update(High), None);
+
+        // The copy continues from the same progress, but independently
+        let mut copy = inputs;
+        assert_eq!(copy.start.update(High), Some(Edge::new(Low, High)));
+        assert_eq!(copy.stop.update(High), None);
+        assert!(!inputs.start.is_high());
+        assert!(inputs.stop.is_low());
+
+        assert_eq!(inputs.start.update(Low), None);
+        assert!(inputs.start.is_low());
+        assert!(copy.start.is_high());
+
+        let original: Debouncer<PinState, u8> = Debouncer::new(2, Low);
+        let mut copy = original;
+        assert_eq!(copy.update(High), None);
+        assert_ne!(copy, original);
+        assert!(original.is_state(Low));
+    }
+
+    #[test]
+    fn test_edge_delta() {
+        use PinState::{High, Low};
+
+        assert_eq!(Edge::new(Low, High).delta(), 1);
+        assert_eq!(Edge::new(High, Low).delta(), -1);
+
+        // Net movement over a sequence of edges
+        let mut debouncer = SmallPinDebouncer::new(2, PinState::Low);
+        let samples = [High, High, Low, Low, High, High, Low];
+        let net: i32 = debouncer
+            .edges(samples.iter().copied())
+            .map(|edge| edge.delta())
+            .sum();
+        assert_eq!(net, 1);
+    }
+
+    #[test]
+    fn test_edge_code() {
+        use PinState::{High, Low};
+
+        let edges = [
+            (Edge::new(Low, Low), 0b00),
+            (Edge::new(High, Low), 0b01),
+            (Edge::new(Low, High), 0b10),
+            (Edge::new(High, High), 0b11),
+        ];
+        for &(edge, code) in edges.iter() {
+            assert_eq!(edge.to_code(), code);
+            assert_eq!(Edge::from_code(code), Some(edge));
+        }
+
+        assert_eq!(Edge::from_code(0b100), None);
+        assert_eq!(Edge::from_code(u8::MAX), None);
+    }
+
+    #[test]
+    fn test_edge_into_bool() {
+        let rising: bool = Edge::new(PinState::Low, PinState::High).into();
+        assert!(rising);
+
+        let rising: bool = Edge::new(PinState::High, PinState::Low).into();
+        assert!(!rising);
+    }
+
+    #[test]
+    fn test_update_result() {
+        let mut debouncer = SmallPinDebouncer::new(3, PinState::Low);
+        assert_eq!(debouncer.update_result::<()>(Ok(PinState::High)), Ok(None));
+        assert_eq!(debouncer.update_result(Err("nack")), Err("nack"));
+        assert_eq!(debouncer.update_result::<()>(Ok(PinState::High)), Ok(None));
+        assert_eq!(debouncer.update_result(Err("nack")), Err("nack"));
+        assert_eq!(debouncer.update_result(Err("timeout")), Err("timeout"));
+        assert!(!debouncer.is_high());
+        assert_eq!(
+            debouncer.update_result::<()>(Ok(PinState::High)),
+            Ok(Some(Edge::new(PinState::Low, PinState::High)))
+        );
+        assert!(debouncer.is_high());
+    }
+
+    #[test]
+    fn test_edges() {
+        use std::vec::Vec;
+        use PinState::{High, Low};
+
+        let samples = [
+            High, Low, High, High, High, Low, High, Low, Low, Low, High, High,
+        ];
+        let mut debouncer = SmallPinDebouncer::new(3, Low);
+        let edges: Vec<_> = debouncer.edges(samples.iter().copied()).collect();
+        assert_eq!(edges, [Edge::new(Low, High), Edge::new(High, Low)]);
+
+        // The state is kept across calls
+        assert_eq!(
+            debouncer.edges(Some(High)).next(),
+            Some(Edge::new(Low, High))
+        );
+
+        // Only pulled samples are fed
+        let mut debouncer = SmallPinDebouncer::new(2, Low);
+        let mut edges = debouncer.edges(samples.iter().copied());
+        assert_eq!(edges.next(), Some(Edge::new(Low, High)));
+        drop(edges);
+        assert!(debouncer.is_high());
+    }
+
+    #[test]
+    fn test_bytes_round_trip() {
+        let mut debouncer = TinyPinDebouncer::new(4, PinState::Low);
+        assert_eq!(debouncer.to_bytes(), [0, 0, 0, 4]);
+
+        for expected in [[0, 1, 1, 4], [0, 1, 2, 4], [0, 1, 3, 4], [1, 1, 0, 4]].iter() {
+            let _ = debouncer.update(PinState::High);
+            assert_eq!(debouncer.to_bytes(), *expected);
+
+            let restored = TinyPinDebouncer::from_bytes(*expected).unwrap();
+            assert_eq!(restored.snapshot(), debouncer.snapshot());
+        }
+
+        // The restored debouncer continues the transition
+        let mut restored = TinyPinDebouncer::from_bytes([1, 0, 3, 4]).unwrap();
+        assert_eq!(
+            restored.update(PinState::Low),
+            Some(Edge::new(PinState::High, PinState::Low))
+        );
+    }
+
+    #[test]
+    fn test_bytes_round_trip_threshold_one() {
+        let mut debouncer = TinyPinDebouncer::new(1, PinState::Low);
+        let _ = debouncer.update(PinState::High);
+        assert_eq!(debouncer.to_bytes(), [0, 1, 1, 1]);
+
+        let mut restored = TinyPinDebouncer::from_bytes([0, 1, 1, 1]).unwrap();
+        assert_eq!(restored.snapshot(), debouncer.snapshot());
+        assert_eq!(
+            restored.update(PinState::High),
+            Some(Edge::new(PinState::Low, PinState::High))
+        );
+    }
+
+    #[test]
+    fn test_bytes_invalid() {
+        assert_eq!(TinyPinDebouncer::from_bytes([2, 0, 0, 4]), None);
+        assert_eq!(TinyPinDebouncer::from_bytes([0, 0xff, 0, 4]), None);
+        assert_eq!(TinyPinDebouncer::from_bytes([0, 1, 5, 4]), None);
+        assert_eq!(TinyPinDebouncer::from_bytes([0, 0, 0, 0]), None);
+        assert_eq!(TinyPinDebouncer::from_bytes([1, 0, 0, 0]), None);
+        assert_eq!(TinyPinDebouncer::from_bytes([0, 1, 4, 4]), None);
+        assert_eq!(TinyPinDebouncer::from_bytes([0, 1, 0, 4]), None);
+        assert_eq!(TinyPinDebouncer::from_bytes([0, 0, 3, 4]), None);
+        assert_eq!(TinyPinDebouncer::from_bytes([0, 1, 2, 1]), None);
+    }
+
+    /// Every output of `to_bytes` decodes to the same debouncer, and every
+    /// decodable input is an output of `to_bytes`.
+    #[test]
+    fn test_bytes_exhaustive() {
+        for threshold in 1..=u8::MAX {
+            let mut debouncer = TinyPinDebouncer::new(threshold, PinState::Low);
+            for state in [PinState::High, PinState::Low].iter() {
+                let mut bytes = debouncer.to_bytes();
+                while debouncer.update(*state).is_none() {
+                    bytes = debouncer.to_bytes();
+                    let restored = TinyPinDebouncer::from_bytes(bytes).unwrap();
+                    assert_eq!(restored.snapshot(), debouncer.snapshot());
+                }
+                assert_eq!(bytes[2], threshold.max(2) - 1);
+            }
+        }
+
+        for current_state in 0..=1 {
+            for next_state in 0..=1 {
+                for count in 0..=u8::MAX {
+                    for threshold in 0..=u8::MAX {
+                        let bytes = [current_state, next_state, count, threshold];
+                        if let Some(debouncer) = TinyPinDebouncer::from_bytes(bytes) {
+                            assert_eq!(debouncer.to_bytes(), bytes);
+                        }
+                    }
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_ord() {
+        assert!(PinState::Low < PinState::High);
+        assert_eq!(PinState::High.max(PinState::Low), PinState::High);
+
+        let mut states = [PinState::High, PinState::Low, PinState::High];
+        states.sort();
+        assert_eq!(states, [PinState::Low, PinState::High, PinState::High]);
+    }
+
+    #[test]
+    fn test_new_primed_toward() {
+        let samples_to_edge = |mut debouncer: SmallPinDebouncer| {
+            (1..=10)
+                .find(|_| debouncer.update(PinState::High).is_some())
+                .unwrap()
+        };
+
+        let debouncer = SmallPinDebouncer::new_primed_toward(6, PinState::Low, PinState::High);
+        assert!(!debouncer.is_low());
+        assert!(!debouncer.is_high());
+        assert_eq!(samples_to_edge(debouncer), 3);
+        assert_eq!(samples_to_edge(SmallPinDebouncer::new(6, PinState::Low)), 6);
+
+        let debouncer = SmallPinDebouncer::new_primed_toward(5, PinState::Low, PinState::High);
+        assert_eq!(samples_to_edge(debouncer), 3);
+
+        // The head start is lost with the first sample of the initial state
+        let mut debouncer = SmallPinDebouncer::new_primed_toward(6, PinState::Low, PinState::High);
+        assert_eq!(debouncer.update(PinState::Low), None);
+        assert!(debouncer.is_low());
+        assert_eq!(samples_to_edge(debouncer), 6);
+
+        let debouncer = SmallPinDebouncer::new_primed_toward(6, PinState::Low, PinState::Low);
+        assert!(debouncer.is_low());
+        assert_eq!(samples_to_edge(debouncer), 6);
+
+        // No head start below the effective threshold of two
+        let debouncer = SmallPinDebouncer::new_primed_toward(1, PinState::Low, PinState::High);
+        assert!(debouncer.is_low());
+        assert_eq!(samples_to_edge(debouncer), 2);
+        let debouncer = SmallPinDebouncer::new_primed_toward(2, PinState::Low, PinState::High);
+        assert_eq!(samples_to_edge(debouncer), 1);
+    }
+
+    #[test]
+    fn test_from_prelude() {
+        use PinState::{High, Low};
+
+        let prelude = [Low, High, Low, High, High, High, High];
+        let mut debouncer = SmallPinDebouncer::from_prelude(3, &prelude);
+        assert!(debouncer.is_high());
+        assert_eq!(debouncer.update(High), None);
+
+        // Ends in a transition to low
+        let debouncer = SmallPinDebouncer::from_prelude(3, &[High, High, Low]);
+        assert!(!debouncer.is_high());
+        assert!(!debouncer.is_low());
+
+        assert!(SmallPinDebouncer::from_prelude(3, &[]).is_low());
+    }
+
+    #[test]
+    fn test_final_state() {
+        let reads = [true, false, true, true, true, true];
+        assert_eq!(
+            SmallPinDebouncer::final_state(3, PinState::Low, &reads),
+            PinState::High
+        );
+
+        // Ends mid-transition to low
+        let reads = [true, true, true, false, false];
+        assert_eq!(
+            SmallPinDebouncer::final_state(3, PinState::Low, &reads),
+            PinState::High
+        );
+
+        assert_eq!(
+            SmallPinDebouncer::final_state(3, PinState::High, &[]),
+            PinState::High
+        );
+    }
+
+    #[test]
+    fn test_from_active_low() {
+        assert_eq!(PinState::from_active_low(false), PinState::High);
+        assert_eq!(PinState::from_active_low(true), PinState::Low);
+    }
+
+    #[test]
+    fn test_new_const() {
+        const BUTTON: SmallPinDebouncer = SmallPinDebouncer::new_const(2, PinState::Low);
+        static SWITCH: SmallPinDebouncer = SmallPinDebouncer::new_const(3, PinState::High);
+        assert!(SWITCH.is_high());
+
+        let mut debouncer = BUTTON;
+        assert!(debouncer.is_low());
+        assert_eq!(debouncer.update(PinState::High), None);
+        assert_eq!(
+            debouncer.update(PinState::High),
+            Some(Edge::new(PinState::Low, PinState::High))
+        );
+    }
+
+    #[test]
+    fn test_default() {
+        let mut debouncer = SmallPinDebouncer::default();
+        assert!(debouncer.is_low());
+
+        for _ in 0..3 {
+            assert_eq!(debouncer.update(PinState::High), None);
+        }
+        assert_eq!(
+            debouncer.update(PinState::High),
+            Some(Edge::new(PinState::Low, PinState::High))
+        );
+    }
+
+    #[test]
+    fn test_builder() {
+        let mut debouncer = SmallPinDebouncer::builder()
+            .threshold(2)
+            .initial_state(PinState::High)
+            .build();
+        assert!(debouncer.is_high());
+        assert_eq!(debouncer.update(PinState::Low), None);
+        assert_eq!(
+            debouncer.update(PinState::Low),
+            Some(Edge::new(PinState::High, PinState::Low))
+        );
+
+        // Unset values fall back to the defaults
+        let mut debouncer = SmallPinDebouncer::builder().threshold(3).build();
+        assert!(debouncer.is_low());
+        assert_eq!(debouncer.update(PinState::High), None);
+        assert_eq!(debouncer.update(PinState::High), None);
+        assert_eq!(
+            debouncer.update(PinState::High),
+            Some(Edge::new(PinState::Low, PinState::High))
+        );
+    }
+
+    /// Ensure a clone can be advanced speculatively.
+    #[test]
+    fn test_clone() {
+        let mut debouncer = SmallPinDebouncer::new(3, PinState::Low);
+        assert_eq!(debouncer.update(PinState::High), None);
+
+        let mut speculative = debouncer;
+        assert_eq!(speculative.update(PinState::High), None);
+        assert_eq!(
+            speculative.update(PinState::High),
+            Some(Edge::new(PinState::Low, PinState::High))
+        );
+        assert!(speculative.is_high());
+
+        // The original is still one sample into the transition
+        assert!(!debouncer.is_high());
+        assert!(!debouncer.is_low());
+        assert_eq!(debouncer.update(PinState::High), None);
+        assert_eq!(
+            debouncer.update(PinState::High),
+            Some(Edge::new(PinState::Low, PinState::High))
+        );
+    }
+
+    #[test]
+    fn test_try_from_u8() {
+        assert_eq!(PinState::try_from(0), Ok(PinState::Low));
+        assert_eq!(PinState::try_from(1), Ok(PinState::High));
+        assert_eq!(PinState::try_from(2), Err(InvalidPinState(2)));
+        assert_eq!(PinState::try_from(0xff), Err(InvalidPinState(0xff)));
+    }
+
+    #[test]
+    fn test_edge_map() {
+        #[derive(Debug, PartialEq)]
+        enum ButtonEvent {
+            Pressed,
+            Released,
+        }
+
+        let to_event = |state| match state {
+            PinState::High => ButtonEvent::Pressed,
+            PinState::Low => ButtonEvent::Released,
+        };
+
+        assert_eq!(
+            Edge::new(PinState::Low, PinState::High).map(to_event),
+            Edge::new(ButtonEvent::Released, ButtonEvent::Pressed)
+        );
+        assert_eq!(
+            Edge::new(PinState::High, PinState::Low).map(to_event),
+            Edge::new(ButtonEvent::Pressed, ButtonEvent::Released)
+        );
+    }
+
+    #[test]
+    fn test_poll_with() {
+        let mut debouncer = SmallPinDebouncer::new(2, PinState::Low);
+        let mut rising = 0;
+        let mut falling = 0;
+
+        let samples = [
+            PinState::High,
+            PinState::High,
+            PinState::High,
+            PinState::Low,
+            PinState::High,
+            PinState::Low,
+            PinState::Low,
+        ];
+        let expected = [(0, 0), (1, 0), (1, 0), (1, 0), (1, 0), (1, 0), (1, 1)];
+        for (&state, &counts) in samples.iter().zip(expected.iter()) {
+            debouncer.poll_with(state, || rising += 1, || falling += 1);
+            assert_eq!((rising, falling), counts);
+        }
+    }
+
+    #[test]
+    fn test_poll_toggle() {
+        let mut debouncer = SmallPinDebouncer::new(2, PinState::Low);
+
+        // A bouncing press and release
+        let press = [
+            PinState::High,
+            PinState::Low,
+            PinState::High,
+            PinState::High,
+            PinState::High,
+            PinState::Low,
+            PinState::Low,
+        ];
+        let latches: std::vec::Vec<_> = press
+            .iter()
+            .map(|&state| debouncer.poll_toggle(state))
+            .collect();
+        assert_eq!(latches, [false, false, false, true, true, true, true]);
+
+        // Only the presses toggle, not the releases
+        for &expected in &[false, true, false] {
+            let latches: std::vec::Vec<_> = press
+                .iter()
+                .map(|&state| debouncer.poll_toggle(state))
+                .collect();
+            assert_eq!(latches.last(), Some(&expected));
+        }
+    }
+
+    #[test]
+    fn test_threshold_for_duration() {
+        // Exact division
+        assert_eq!(threshold_for_duration(20, 5), 4);
+        assert_eq!(threshold_for_duration(20, 20), 1);
+
+        // Rounding up
+        assert_eq!(threshold_for_duration(20, 3), 7);
+        assert_eq!(threshold_for_duration(21, 20), 2);
+
+        // Sub-period durations
+        assert_eq!(threshold_for_duration(1, 20), 1);
+        assert_eq!(threshold_for_duration(0, 20), 1);
+
+        // Saturation
+        assert_eq!(threshold_for_duration(1000, 1), u8::MAX);
+        assert_eq!(threshold_for_duration(u32::MAX, 1), u8::MAX);
+        assert_eq!(threshold_for_duration(20, 0), u8::MAX);
+    }
+
+    #[test]
+    fn test_edge_same_transition() {
+        let rising = Edge::new(PinState::Low, PinState::High);
+        let falling = Edge::new(PinState::High, PinState::Low);
+
+        assert!(rising.same_transition(&rising));
+        assert!(rising.same_transition(&falling));
+        assert!(falling.same_transition(&rising));
+        assert!(!rising.same_transition(&Edge::new(PinState::Low, PinState::Low)));
+    }
+
+    #[test]
+    fn test_toggle() {
+        let mut state = PinState::Low;
+        state.toggle();
+        assert_eq!(state, PinState::High);
+        state.toggle();
+        assert_eq!(state, PinState::Low);
+
+        for _ in 0..6 {
+            state.toggle();
+        }
+        assert_eq!(state, PinState::Low);
+
+        assert_eq!(PinState::Low.toggled(), PinState::High);
+        assert_eq!(PinState::High.toggled(), PinState::Low);
+        assert_eq!(PinState::High.toggled().toggled(), PinState::High);
+    }
+
+    #[cfg(feature = "alloc")]
+    #[test]
+    fn test_replay() {
+        use PinState::{High, Low};
+
+        let samples = [High, Low, High, High, High, Low, Low, Low, High];
+        let (debouncer, edges) = SmallPinDebouncer::replay(3, Low, samples.iter().copied());
+        assert_eq!(edges, [Edge::new(Low, High), Edge::new(High, Low)]);
+        assert!(!debouncer.is_high());
+        assert!(!debouncer.is_low());
+        assert!(debouncer.is_armed());
+    }
+
+    #[cfg(feature = "alloc")]
+    #[test]
+    fn test_sweep_thresholds() {
+        use PinState::{High, Low};
+
+        // Three presses, each bouncing for a few samples on both edges
+        let mut samples = std::vec::Vec::new();
+        for _ in 0..3 {
+            samples.extend_from_slice(&[High, Low, High, High, Low, Low, High, Low]);
+            samples.extend_from_slice(&[High; 12]);
+            samples.extend_from_slice(&[Low, High, Low, Low, High, High, Low, High]);
+            samples.extend_from_slice(&[Low; 12]);
+        }
+
+        let sweep = sweep_thresholds(&samples, 1..=16, Low);
+        assert_eq!(sweep.len(), 16);
+        assert!(sweep.iter().map(|&(threshold, _)| threshold).eq(1..=16));
+        assert!(sweep.windows(2).all(|pair| pair[0].1 >= pair[1].1));
+
+        // Bouncing is filtered from a threshold of 3 on, until the presses
+        // themselves are too short
+        assert!(sweep[0].1 > 6);
+        assert!(sweep[2..12].iter().all(|&(_, edges)| edges == 6));
+        assert_eq!(sweep[15], (16, 0));
+
+        assert_eq!(sweep_thresholds(&samples, 4..=4, Low), [(4, 6)]);
+    }
+
+    #[test]
+    fn test_edge_direction() {
+        use crate::debouncer::Direction;
+
+        assert_eq!(
+            Edge::new(PinState::Low, PinState::High).direction(),
+            Direction::Forward
+        );
+        assert_eq!(
+            Edge::new(PinState::High, PinState::Low).direction(),
+            Direction::Backward
+        );
+    }
+
+    #[test]
+    fn test_counter_types() {
+        let mut tiny = TinyPinDebouncer::new(3, PinState::Low);
+        let mut wide = WidePinDebouncer::new(3, PinState::Low);
+        let mut large: Debouncer<PinState, u32> = Debouncer::new(3, PinState::Low);
+        let samples = [
+            PinState::High,
+            PinState::High,
+            PinState::Low,
+            PinState::High,
+            PinState::High,
+            PinState::High,
+        ];
+        for &state in samples.iter() {
+            let edge = tiny.update(state);
+            assert_eq!(wide.update(state), edge);
+            assert_eq!(large.update(state), edge);
+        }
+        assert!(tiny.is_state(PinState::High));
+
+        // Very high thresholds
+        let mut wide = WidePinDebouncer::new(1000, PinState::Low);
+        for _ in 0..999 {
+            assert_eq!(wide.update(PinState::High), None);
+        }
+        assert_eq!(
+            wide.update(PinState::High),
+            Some(Edge::new(PinState::Low, PinState::High))
+        );
+    }
+
+    cfg_no_optional_counters! {
+        /// Ensure the size of the counter types.
+        #[test]
+        fn test_counter_types_ram_consumption() {
+            assert_eq!(std::mem::size_of::<TinyPinDebouncer>(), 4);
+            assert_eq!(std::mem::size_of::<WidePinDebouncer>(), 6);
+            assert_eq!(std::mem::size_of::<Debouncer<PinState, u32>>(), 12);
+        }
+    }
+
+    /// Feed pseudo-random samples and check that an edge fires exactly after
+    /// `threshold` consecutive samples differing from the committed state.
+    #[test]
+    fn test_property_exact_threshold() {
+        for threshold in 2..=16u8 {
+            for seed in 1..=8u32 {
+                let mut random = seed.wrapping_mul(0x9e37_79b9) | 1;
+                let mut debouncer = SmallPinDebouncer::new(threshold, PinState::Low);
+
+                // Reference model
+                let mut committed = PinState::Low;
+                let mut run_length = 0;
+                let mut state = PinState::Low;
+
+                for _ in 0..10_000 {
+                    // Xorshift, flipping the raw level with a probability
+                    // of 1/8 to get runs of various lengths
+                    random ^= random << 13;
+                    random ^= random >> 17;
+                    random ^= random << 5;
+                    if random % 8 == 0 {
+                        state = !state;
+                    }
+
+                    run_length = if state == committed {
+                        0
+                    } else {
+                        run_length + 1
+                    };
+                    let expected = if run_length == threshold {
+                        let edge = Edge::new(committed, state);
+                        committed = state;
+                        run_length = 0;
+                        Some(edge)
+                    } else {
+                        None
+                    };
+
+                    assert_eq!(debouncer.update(state), expected);
+                    assert_eq!(
+                        debouncer.is_high(),
+                        committed == PinState::High && run_length == 0
+                    );
+                    assert_eq!(
+                        debouncer.is_low(),
+                        committed == PinState::Low && run_length == 0
+                    );
+                }
+            }
+        }
+    }
+
+    /// Feeds the bits of `data` as samples, after a first byte selecting the
+    /// threshold, and checks the invariants after every sample.
+    fn check_invariants(data: &[u8]) {
+        let (&threshold, data) = match data.split_first() {
+            Some(split) => split,
+            None => return,
+        };
+        let inner = match Debouncer::checked_new(threshold, PinState::Low) {
+            Ok(inner) => inner,
+            Err(error) => {
+                assert_eq!(threshold, 0);
+                assert_eq!(error, DebouncerError::ZeroThreshold);
+                return;
+            }
+        };
+        let mut debouncer = SmallPinDebouncer {
+            inner,
+            latch: false,
+        };
+        // A threshold of one acts like two
+        let effective_threshold = threshold.max(2);
+        for &byte in data {
+            for bit in 0..8 {
+                let state = if byte >> bit & 1 == 1 {
+                    PinState::High
+                } else {
+                    PinState::Low
+                };
+                let before = debouncer.inner.snapshot();
+                let edge = debouncer.update(state);
+                let after = debouncer.inner.snapshot();
+
+                assert!(!(debouncer.is_high() && debouncer.is_low()));
+                assert_eq!(after.threshold, threshold);
+                if after.current_state == after.next_state {
+                    assert_eq!(after.repetition_count, 0);
+                } else {
+                    assert!(after.repetition_count >= 1);
+                    assert!(after.repetition_count < effective_threshold);
+                }
+
+                match edge {
+                    Some(edge) => {
+                        let (from, to) = edge.into_parts();
+                        assert_ne!(from, to);
+                        assert_eq!(from, before.current_state);
+                        assert_eq!(to, state);
+                        assert_eq!(before.repetition_count, effective_threshold - 1);
+                    }
+                    None => assert_eq!(after.current_state, before.current_state),
+                }
+            }
+        }
+    }
+
+    /// Checks the invariants for pseudo-random byte streams, with a bias
+    /// towards small thresholds and long runs so that edges actually occur.
+    ///
+    /// The number of streams defaults to 2000 and can be raised for a longer
+    /// run with `DERICO_FUZZ_ITERATIONS=1000000 cargo test --release fuzz`.
+    #[test]
+    fn test_fuzz_invariants() {
+        let iterations = std::env::var("DERICO_FUZZ_ITERATIONS")
+            .ok()
+            .and_then(|iterations| iterations.parse().ok())
+            .unwrap_or(2000u32);
+
+        let mut random = 0x2545_f491u32;
+        let mut next = move || {
+            random ^= random << 13;
+            random ^= random >> 17;
+            random ^= random << 5;
+            random
+        };
+
+        check_invariants(&[]);
+        check_invariants(&[0, 0xff]);
+        check_invariants(&[1, 0x0f, 0x55]);
+        for _ in 0..iterations {
+            let len = (next() % 64) as usize;
+            let mut data = std::vec![(next() % 16) as u8];
+            data.extend((0..len).map(|_| match next() % 4 {
+                0 => 0x00,
+                1 => 0xff,
+                _ => next() as u8,
+            }));
+            check_invariants(&data);
+        }
+    }
+
+    #[test]
+    fn test_not() {
+        assert_eq!(!PinState::High, PinState::Low);
+        assert_eq!(!PinState::Low, PinState::High);
+        assert_eq!(!!PinState::Low, PinState::Low);
+    }
 
     #[test]
     fn test_rising_edge() {
@@ -54,7 +1289,7 @@ mod tests {
         // Further highs do not indicate a rising edge anymore
         assert_eq!(debouncer.update(PinState::High), None);
 
-        // A low state does not reset counting
+        // A single low sample is discarded, so no second rising edge follows
         assert_eq!(debouncer.update(PinState::Low), None);
         assert_eq!(debouncer.update(PinState::High), None);
         assert_eq!(debouncer.update(PinState::High), None);
@@ -81,7 +1316,7 @@ mod tests {
         // Further lowss do not indicate a rising edge anymore
         assert_eq!(debouncer.update(PinState::Low), None);
 
-        // A high state does not reset counting
+        // A single high sample is discarded, so no second falling edge follows
         assert_eq!(debouncer.update(PinState::High), None);
         assert_eq!(debouncer.update(PinState::Low), None);
         assert_eq!(debouncer.update(PinState::Low), None);
@@ -117,7 +1352,7 @@ mod tests {
         assert!(!debouncer.is_high());
 
         // Depressed updates don't change the situation
-        debouncer.update(PinState::Low);
+        assert_eq!(debouncer.update(PinState::Low), None);
         assert!(debouncer.is_low());
         assert!(!debouncer.is_high());
 
@@ -142,26 +1377,28 @@ mod tests {
         assert!(debouncer.is_high());
     }
 
-    /// Ensure the promised low RAM consumption.
-    #[test]
-    fn test_ram_consumption() {
-        // Regular debouncers
-        assert_eq!(
-            std::mem::size_of_val(&SmallPinDebouncer::new(2, PinState::Low)),
-            4
-        );
-        assert_eq!(
-            std::mem::size_of_val(&SmallPinDebouncer::new(8, PinState::Low)),
-            4
-        );
-        assert_eq!(
-            std::mem::size_of_val(&SmallPinDebouncer::new(9, PinState::Low)),
-            4
-        );
-        assert_eq!(
-            std::mem::size_of_val(&SmallPinDebouncer::new(16, PinState::Low)),
-            4
-        );
+    cfg_no_optional_counters! {
+        /// Ensure the promised low RAM consumption.
+        #[test]
+        fn test_ram_consumption() {
+            // Regular debouncers
+            assert_eq!(
+                std::mem::size_of_val(&SmallPinDebouncer::new(2, PinState::Low)),
+                5
+            );
+            assert_eq!(
+                std::mem::size_of_val(&SmallPinDebouncer::new(8, PinState::Low)),
+                5
+            );
+            assert_eq!(
+                std::mem::size_of_val(&SmallPinDebouncer::new(9, PinState::Low)),
+                5
+            );
+            assert_eq!(
+                std::mem::size_of_val(&SmallPinDebouncer::new(16, PinState::Low)),
+                5
+            );
+        }
     }
 
     /// Ensure that the initial state can be specified.
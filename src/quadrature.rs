@@ -0,0 +1,104 @@
+#![deny(unsafe_code)]
+
+use super::pin::{PinState, SmallPinDebouncer};
+
+/// The direction of a rotary encoder step.
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum Rotation {
+    Clockwise,
+    CounterClockwise,
+}
+
+/// Debounces both channels of a quadrature encoder and decodes the steps.
+///
+/// A step is reported on every debounced rising edge of channel A. If
+/// channel B is low at that time, channel A leads and the step is
+/// `Clockwise`, otherwise it is `CounterClockwise`. The level of channel B is
+/// taken before its sample of the same update, so an edge of B in the same
+/// update does not affect the direction.
+#[derive(Debug)]
+pub struct QuadratureDebouncer {
+    a: SmallPinDebouncer,
+    b: SmallPinDebouncer,
+    b_level: PinState,
+}
+
+impl QuadratureDebouncer {
+    /// Creates a decoder resting with both channels low.
+    pub fn new(threshold: u8) -> Self {
+        QuadratureDebouncer {
+            a: SmallPinDebouncer::new(threshold, PinState::Low),
+            b: SmallPinDebouncer::new(threshold, PinState::Low),
+            b_level: PinState::Low,
+        }
+    }
+
+    #[must_use = "a detected step should be handled"]
+    pub fn update(&mut self, a: PinState, b: PinState) -> Option<Rotation> {
+        let rotation = match self.a.update(a).map(|edge| edge.into_parts()) {
+            Some((PinState::Low, PinState::High)) if self.b_level == PinState::Low => {
+                Some(Rotation::Clockwise)
+            }
+            Some((PinState::Low, PinState::High)) => Some(Rotation::CounterClockwise),
+            _ => None,
+        };
+
+        if let Some(edge) = self.b.update(b) {
+            self.b_level = edge.into_parts().1;
+        }
+
+        rotation
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use PinState::{High, Low};
+
+    fn step(
+        decoder: &mut QuadratureDebouncer,
+        levels: &[(PinState, PinState)],
+    ) -> Option<Rotation> {
+        levels
+            .iter()
+            .filter_map(|&(a, b)| decoder.update(a, b))
+            .last()
+    }
+
+    /// Every level of the Gray code is held for three samples.
+    fn hold(levels: &[(PinState, PinState)]) -> std::vec::Vec<(PinState, PinState)> {
+        levels
+            .iter()
+            .flat_map(|&level| core::iter::repeat(level).take(3))
+            .collect()
+    }
+
+    #[test]
+    fn test_clockwise() {
+        let mut decoder = QuadratureDebouncer::new(2);
+        let levels = hold(&[(High, Low), (High, High), (Low, High), (Low, Low)]);
+        assert_eq!(step(&mut decoder, &levels), Some(Rotation::Clockwise));
+
+        // With bouncing on channel A
+        let mut levels = std::vec![(High, Low), (Low, Low)];
+        levels.extend(hold(&[(High, Low), (High, High), (Low, High), (Low, Low)]));
+        assert_eq!(step(&mut decoder, &levels), Some(Rotation::Clockwise));
+    }
+
+    #[test]
+    fn test_counter_clockwise() {
+        let mut decoder = QuadratureDebouncer::new(2);
+        let levels = hold(&[(Low, High), (High, High), (High, Low), (Low, Low)]);
+        assert_eq!(
+            step(&mut decoder, &levels),
+            Some(Rotation::CounterClockwise)
+        );
+
+        // A glitch on channel B is ignored
+        let mut levels = std::vec![(Low, High)];
+        levels.extend(hold(&[(High, Low), (High, High), (Low, High), (Low, Low)]));
+        assert_eq!(step(&mut decoder, &levels), Some(Rotation::Clockwise));
+    }
+}
@@ -0,0 +1,118 @@
+#![deny(unsafe_code)]
+
+use core::pin::Pin;
+use core::task::{Context, Poll};
+
+use futures_core::Stream;
+
+use super::debouncer::Edge;
+use super::pin::{PinState, SmallPinDebouncer};
+
+/// A stream yielding the debounced edges of a stream of samples.
+#[derive(Debug)]
+pub struct DebounceStream<S> {
+    stream: S,
+    debouncer: SmallPinDebouncer,
+}
+
+impl<S> DebounceStream<S>
+where
+    S: Stream<Item = PinState> + Unpin,
+{
+    pub fn new(stream: S, threshold: u8, inital_state: PinState) -> Self {
+        DebounceStream {
+            stream,
+            debouncer: SmallPinDebouncer::new(threshold, inital_state),
+        }
+    }
+}
+
+impl<S> Stream for DebounceStream<S>
+where
+    S: Stream<Item = PinState> + Unpin,
+{
+    type Item = Edge<PinState>;
+
+    /// Drains all ready samples until an edge commits, the inner stream ends
+    /// or it is pending.
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        let this = self.get_mut();
+        loop {
+            match Pin::new(&mut this.stream).poll_next(cx) {
+                Poll::Ready(Some(state)) => {
+                    if let Some(edge) = this.debouncer.update(state) {
+                        return Poll::Ready(Some(edge));
+                    }
+                }
+                Poll::Ready(None) => return Poll::Ready(None),
+                Poll::Pending => return Poll::Pending,
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::vec::Vec;
+
+    use futures::executor::block_on;
+    use futures::stream::{self, StreamExt};
+    use futures::task::noop_waker;
+
+    use PinState::{High, Low};
+
+    #[test]
+    fn test_debounce_stream() {
+        let samples = stream::iter([High, Low, High, High, High, Low, Low, Low].iter().copied());
+        let edges: Vec<_> = block_on(DebounceStream::new(samples, 3, Low).collect());
+        assert_eq!(edges, [Edge::new(Low, High), Edge::new(High, Low)]);
+    }
+
+    /// A stream which is pending before every sample.
+    struct Sluggish<'a> {
+        samples: &'a [PinState],
+        ready: bool,
+    }
+
+    impl Stream for Sluggish<'_> {
+        type Item = PinState;
+
+        fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<PinState>> {
+            if !self.ready {
+                self.ready = true;
+                cx.waker().wake_by_ref();
+                return Poll::Pending;
+            }
+
+            self.ready = false;
+            match self.samples.split_first() {
+                Some((&state, rest)) => {
+                    self.samples = rest;
+                    Poll::Ready(Some(state))
+                }
+                None => Poll::Ready(None),
+            }
+        }
+    }
+
+    #[test]
+    fn test_pending() {
+        let samples = Sluggish {
+            samples: &[High, High],
+            ready: false,
+        };
+        let mut stream = DebounceStream::new(samples, 2, Low);
+        let waker = noop_waker();
+        let mut cx = Context::from_waker(&waker);
+
+        assert_eq!(stream.poll_next_unpin(&mut cx), Poll::Pending);
+        assert_eq!(stream.poll_next_unpin(&mut cx), Poll::Pending);
+        assert_eq!(
+            stream.poll_next_unpin(&mut cx),
+            Poll::Ready(Some(Edge::new(Low, High)))
+        );
+        assert_eq!(stream.poll_next_unpin(&mut cx), Poll::Pending);
+        assert_eq!(stream.poll_next_unpin(&mut cx), Poll::Ready(None));
+    }
+}
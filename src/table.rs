@@ -0,0 +1,74 @@
+#![deny(unsafe_code)]
+
+use super::debouncer::Edge;
+
+/// Maps debounced edges to actions, e.g. to drive a state machine.
+///
+/// The table borrows its `(edge, action)` pairs and looks them up linearly,
+/// which is meant for a handful of transitions.
+#[derive(Debug, Clone, Copy)]
+pub struct TransitionTable<'a, T, A> {
+    transitions: &'a [(Edge<T>, A)],
+}
+
+impl<'a, T, A> TransitionTable<'a, T, A>
+where
+    T: PartialEq,
+{
+    pub fn new(transitions: &'a [(Edge<T>, A)]) -> Self {
+        TransitionTable { transitions }
+    }
+
+    /// Returns the action of the first pair matching `edge`.
+    pub fn lookup(&self, edge: &Edge<T>) -> Option<&'a A> {
+        self.transitions
+            .iter()
+            .find(|(transition, _)| transition == edge)
+            .map(|(_, action)| action)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::pin::{PinState, SmallPinDebouncer};
+
+    #[derive(Debug, PartialEq)]
+    enum Action {
+        LightOn,
+        LightOff,
+    }
+
+    #[test]
+    fn test_lookup() {
+        let transitions = [
+            (Edge::new(PinState::Low, PinState::High), Action::LightOn),
+            (Edge::new(PinState::High, PinState::Low), Action::LightOff),
+        ];
+        let table = TransitionTable::new(&transitions);
+
+        assert_eq!(
+            table.lookup(&Edge::new(PinState::Low, PinState::High)),
+            Some(&Action::LightOn)
+        );
+        assert_eq!(
+            table.lookup(&Edge::new(PinState::High, PinState::Low)),
+            Some(&Action::LightOff)
+        );
+        assert_eq!(table.lookup(&Edge::new(PinState::Low, PinState::Low)), None);
+    }
+
+    #[test]
+    fn test_lookup_miss() {
+        let transitions = [(Edge::new(PinState::Low, PinState::High), Action::LightOn)];
+        let table = TransitionTable::new(&transitions);
+        let mut debouncer = SmallPinDebouncer::new(1, PinState::High);
+
+        assert_eq!(debouncer.update(PinState::Low), None);
+        let edge = debouncer.update(PinState::Low).unwrap();
+        assert_eq!(table.lookup(&edge), None);
+
+        let table: TransitionTable<PinState, Action> = TransitionTable::new(&[]);
+        assert_eq!(table.lookup(&edge), None);
+    }
+}
@@ -0,0 +1,117 @@
+#![deny(unsafe_code)]
+
+use super::debouncer::Edge;
+
+/// A debouncer confirming a state once it was stable for a minimum duration.
+///
+/// Instead of counting samples, every sample is accompanied by a timestamp in
+/// arbitrary ticks. A candidate state is committed once it was sampled
+/// continuously for at least `min_stable` ticks. Wrapping tick counters are
+/// supported, as long as `min_stable` is shorter than the wrap period.
+#[derive(Debug)]
+pub struct TimedDebouncer<T, D> {
+    current_state: T,
+    next_state: T,
+    first_seen: D,
+    min_stable: D,
+}
+
+impl<T, D> TimedDebouncer<T, D>
+where
+    T: PartialEq + Copy,
+    D: num::traits::Zero + num::traits::WrappingSub + PartialOrd + Copy,
+{
+    pub fn new(min_stable: D, inital_state: T) -> Self {
+        TimedDebouncer {
+            current_state: inital_state,
+            next_state: inital_state,
+            first_seen: D::zero(),
+            min_stable,
+        }
+    }
+
+    #[must_use = "a detected edge should be handled"]
+    pub fn update(&mut self, state: T, now: D) -> Option<Edge<T>> {
+        if self.current_state == state {
+            self.next_state = state;
+
+            return None;
+        }
+
+        if self.next_state != state {
+            self.next_state = state;
+            self.first_seen = now;
+        }
+
+        if now.wrapping_sub(&self.first_seen) >= self.min_stable {
+            let from_state = self.current_state;
+
+            self.current_state = state;
+
+            Some(Edge::new(from_state, state))
+        } else {
+            None
+        }
+    }
+
+    pub fn is_state(&self, state: T) -> bool {
+        self.current_state == self.next_state && self.current_state == state
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::pin::PinState;
+
+    #[test]
+    fn test_stable_duration() {
+        let mut debouncer: TimedDebouncer<PinState, u64> = TimedDebouncer::new(20, PinState::Low);
+        assert!(debouncer.is_state(PinState::Low));
+
+        // Irregular sampling, but only 19 ticks stable
+        assert_eq!(debouncer.update(PinState::High, 100), None);
+        assert_eq!(debouncer.update(PinState::High, 101), None);
+        assert_eq!(debouncer.update(PinState::High, 119), None);
+        assert!(!debouncer.is_state(PinState::High));
+
+        // A glitch restarts the timer
+        assert_eq!(debouncer.update(PinState::Low, 121), None);
+        assert_eq!(debouncer.update(PinState::High, 125), None);
+        assert_eq!(debouncer.update(PinState::High, 144), None);
+        assert_eq!(
+            debouncer.update(PinState::High, 145),
+            Some(Edge::new(PinState::Low, PinState::High))
+        );
+        assert!(debouncer.is_state(PinState::High));
+        assert_eq!(debouncer.update(PinState::High, 1000), None);
+
+        // A single late sample suffices once enough time has passed
+        assert_eq!(debouncer.update(PinState::Low, 1001), None);
+        assert_eq!(
+            debouncer.update(PinState::Low, 5000),
+            Some(Edge::new(PinState::High, PinState::Low))
+        );
+    }
+
+    #[test]
+    fn test_zero_duration() {
+        let mut debouncer: TimedDebouncer<PinState, u32> = TimedDebouncer::new(0, PinState::Low);
+        assert_eq!(
+            debouncer.update(PinState::High, 7),
+            Some(Edge::new(PinState::Low, PinState::High))
+        );
+        assert_eq!(debouncer.update(PinState::High, 7), None);
+    }
+
+    #[test]
+    fn test_wrapping_ticks() {
+        let mut debouncer: TimedDebouncer<PinState, u16> = TimedDebouncer::new(10, PinState::High);
+        assert_eq!(debouncer.update(PinState::Low, u16::MAX - 4), None);
+        assert_eq!(debouncer.update(PinState::Low, 3), None);
+        assert_eq!(
+            debouncer.update(PinState::Low, 5),
+            Some(Edge::new(PinState::High, PinState::Low))
+        );
+    }
+}
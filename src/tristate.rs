@@ -0,0 +1,91 @@
+#![deny(unsafe_code)]
+
+use super::debouncer::{Debouncer, Edge};
+
+/// The level of an input which may also be left floating.
+#[derive(Debug, PartialEq, Clone, Copy)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum TriState {
+    Low,
+    High,
+    Floating,
+}
+
+/// Debounces a tri-state input, treating `Floating` like any other level.
+#[derive(Debug, Clone, Copy)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct TriStateDebouncer {
+    inner: Debouncer<TriState, u8>,
+}
+
+impl TriStateDebouncer {
+    pub fn new(threshold: u8, inital_state: TriState) -> Self {
+        TriStateDebouncer {
+            inner: Debouncer::new(threshold, inital_state),
+        }
+    }
+
+    #[must_use = "a detected edge should be handled"]
+    pub fn update(&mut self, state: TriState) -> Option<Edge<TriState>> {
+        self.inner.update(state)
+    }
+
+    pub fn is_high(&self) -> bool {
+        self.inner.is_state(TriState::High)
+    }
+
+    pub fn is_low(&self) -> bool {
+        self.inner.is_state(TriState::Low)
+    }
+
+    pub fn is_floating(&self) -> bool {
+        self.inner.is_state(TriState::Floating)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use TriState::{Floating, High, Low};
+
+    #[test]
+    fn test_all_transitions() {
+        let mut debouncer = TriStateDebouncer::new(3, Low);
+        assert!(debouncer.is_low());
+
+        for &(from, to) in [
+            (Low, High),
+            (High, Floating),
+            (Floating, Low),
+            (Low, Floating),
+            (Floating, High),
+            (High, Low),
+        ]
+        .iter()
+        {
+            assert_eq!(debouncer.update(to), None);
+            assert_eq!(debouncer.update(to), None);
+            assert_eq!(debouncer.update(to), Some(Edge::new(from, to)));
+        }
+        assert!(debouncer.is_low());
+    }
+
+    #[test]
+    fn test_floating_threshold() {
+        let mut debouncer = TriStateDebouncer::new(4, High);
+        assert_eq!(debouncer.update(Floating), None);
+        assert_eq!(debouncer.update(Floating), None);
+        assert_eq!(debouncer.update(Floating), None);
+        assert!(!debouncer.is_floating());
+        assert!(!debouncer.is_high());
+        assert_eq!(debouncer.update(Floating), Some(Edge::new(High, Floating)));
+        assert!(debouncer.is_floating());
+
+        // A floating glitch restarts the count as well
+        assert_eq!(debouncer.update(Low), None);
+        assert_eq!(debouncer.update(Low), None);
+        assert_eq!(debouncer.update(Floating), None);
+        assert!(debouncer.is_floating());
+    }
+}
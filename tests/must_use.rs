@@ -0,0 +1,6 @@
+/// Ensure ignoring a detected edge is linted.
+#[test]
+fn test_unused_edge() {
+    let cases = trybuild::TestCases::new();
+    cases.compile_fail("tests/ui/unused_edge.rs");
+}
@@ -0,0 +1,13 @@
+#![deny(unused_must_use)]
+
+use derico::debouncer::Debouncer;
+use derico::pin::{PinState, SmallPinDebouncer};
+
+fn main() {
+    let mut debouncer: Debouncer<PinState, u8> = Debouncer::new(2, PinState::Low);
+    debouncer.update(PinState::High);
+    debouncer.force_state(PinState::High);
+
+    let mut pin_debouncer = SmallPinDebouncer::new(2, PinState::Low);
+    pin_debouncer.update(PinState::High);
+}